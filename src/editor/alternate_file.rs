@@ -0,0 +1,56 @@
+use std::path::Path;
+
+/// Used when the config file doesn't set `alternate_files`.
+const DEFAULT_RULES: &str = "src/*.rs:tests/*.rs, *.c:*.h, *.cc:*.h, *.cpp:*.hpp";
+
+/// Parses rules like `src/*.rs:tests/*.rs, *.c:*.h`: pairs of patterns
+/// with one `*` each, separated by `:`. Returns `None` if a rule is
+/// malformed.
+pub fn parse_rules(value: &str) -> Option<Vec<(String, String)>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(|rule| {
+            let (first, second) = rule.split_once(':')?;
+            let (first, second) = (first.trim(), second.trim());
+            [first, second]
+                .iter()
+                .all(|pattern| pattern.matches('*').count() == 1)
+                .then(|| (first.into(), second.into()))
+        })
+        .collect()
+}
+
+pub fn default_rules() -> Vec<(String, String)> {
+    parse_rules(DEFAULT_RULES).unwrap_or_default()
+}
+
+/// The first existing file that `path` maps to under `rules`, trying both
+/// directions of each rule in order.
+pub fn find(path: &str, rules: &[(String, String)]) -> Option<String> {
+    rules
+        .iter()
+        .flat_map(|(first, second)| [(first, second), (second, first)])
+        .filter_map(|(from, to)| {
+            let (dir, stem) = match_pattern(from, path)?;
+            Some(format!("{dir}{}", to.replacen('*', stem, 1)))
+        })
+        .find(|candidate| candidate != path && Path::new(candidate).is_file())
+}
+
+/// Matches `pattern` against the end of `path`, starting at a directory
+/// boundary. Returns the directory before the match and the part `*`
+/// stood for. The match closest to the file name wins.
+fn match_pattern<'a>(pattern: &str, path: &'a str) -> Option<(&'a str, &'a str)> {
+    let (before, after) = pattern.split_once('*')?;
+    path.char_indices()
+        .map(|(idx, _)| idx)
+        .filter(|&idx| idx == 0 || path[..idx].ends_with('/'))
+        .rev()
+        .find_map(|idx| {
+            let (dir, rest) = path.split_at(idx);
+            let stem = rest.strip_prefix(before)?.strip_suffix(after)?;
+            (!stem.is_empty()).then_some((dir, stem))
+        })
+}
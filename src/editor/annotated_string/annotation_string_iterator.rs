@@ -22,10 +22,9 @@ impl<'a> Iterator for AnnotatedStringIterator<'a> {
             .annotated_string
             .annotations
             .iter()
-            .filter(|annotation| {
+            .rfind(|annotation| {
                 annotation.start <= self.current_idx && annotation.end > self.current_idx
             })
-            .last()
         {
             let end_idx = min(annotation.end, self.annotated_string.string.len());
             let start_idx = self.current_idx;
@@ -45,9 +44,9 @@ impl<'a> Iterator for AnnotatedStringIterator<'a> {
 
         let start_idx = self.current_idx;
         self.current_idx = end_idx;
-        return Some(AnnotatedStringPart {
+        Some(AnnotatedStringPart {
             string: &self.annotated_string.string[start_idx..end_idx],
             annotation_type: None,
-        });
+        })
     }
 }
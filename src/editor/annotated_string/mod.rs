@@ -2,7 +2,7 @@
 use crate::prelude::*;
 
 use std::{
-    cmp::{max, min},
+    cmp::min,
     fmt::{self, Display},
 };
 
@@ -25,6 +25,10 @@ impl AnnotatedString {
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.string.is_empty()
+    }
+
     pub fn add_annotation(
         &mut self,
         annotation_type: AnnotationType,
@@ -56,50 +60,33 @@ impl AnnotatedString {
 
         self.string.replace_range(start..end, new_string);
 
-        let replaced_range_len = end.saturating_sub(start);
-        let shortened = new_string.len() < replaced_range_len;
-        let len_difference = new_string.len().abs_diff(replaced_range_len);
-
-        if len_difference == 0 {
-            return;
-        }
-
+        // Offsets inside the replaced range snap to its new bounds, so an
+        // annotation touching the old text covers all of the replacement and
+        // never starts or ends inside a multi-byte character of it.
+        let new_end = start.saturating_add(new_string.len());
+        let shift = |idx: ByteIdx| idx.saturating_sub(end).saturating_add(new_end);
         self.annotations.iter_mut().for_each(|annotation| {
             annotation.start = if annotation.start >= end {
-                if shortened {
-                    annotation.start.saturating_sub(len_difference)
-                } else {
-                    annotation.start.saturating_add(len_difference)
-                }
+                shift(annotation.start)
             } else if annotation.start >= start {
-                if shortened {
-                    max(start, annotation.start.saturating_sub(len_difference))
-                } else {
-                    min(end, annotation.start.saturating_add(len_difference))
-                }
+                start
             } else {
                 annotation.start
             };
 
             annotation.end = if annotation.end >= end {
-                if shortened {
-                    annotation.end.saturating_sub(len_difference)
-                } else {
-                    annotation.end.saturating_add(len_difference)
-                }
-            } else if annotation.end >= start {
-                if shortened {
-                    max(start, annotation.end.saturating_sub(len_difference))
-                } else {
-                    min(end, annotation.end.saturating_add(len_difference))
-                }
+                shift(annotation.end)
+            } else if annotation.end > start {
+                new_end
             } else {
                 annotation.end
-            }
+            };
         });
 
-        self.annotations.retain(|annotation| {
-            annotation.start < annotation.end && annotation.start < self.string.len()
+        let len = self.string.len();
+        self.annotations.retain_mut(|annotation| {
+            annotation.end = min(annotation.end, len);
+            annotation.start < annotation.end
         });
     }
 }
@@ -121,3 +108,96 @@ impl<'a> IntoIterator for &'a AnnotatedString {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotated(string: &str, spans: &[(ByteIdx, ByteIdx)]) -> AnnotatedString {
+        let mut annotated_string = AnnotatedString::from(string);
+        for &(start, end) in spans {
+            annotated_string.add_annotation(AnnotationType::Match, start, end);
+        }
+        annotated_string
+    }
+
+    fn spans(annotated_string: &AnnotatedString) -> Vec<(ByteIdx, ByteIdx)> {
+        annotated_string
+            .annotations
+            .iter()
+            .map(|annotation| (annotation.start, annotation.end))
+            .collect()
+    }
+
+    #[test]
+    fn replace_before_annotation_shifts_it() {
+        let mut string = annotated("abcdef", &[(3, 5)]);
+        string.replace(0, 1, "XYZ");
+        assert_eq!(string.to_string(), "XYZbcdef");
+        assert_eq!(spans(&string), [(5, 7)]);
+
+        string.replace(0, 3, "");
+        assert_eq!(string.to_string(), "bcdef");
+        assert_eq!(spans(&string), [(2, 4)]);
+    }
+
+    #[test]
+    fn replace_after_annotation_keeps_it() {
+        let mut string = annotated("abcdef", &[(1, 3)]);
+        string.replace(4, 6, "XYZ");
+        assert_eq!(string.to_string(), "abcdXYZ");
+        assert_eq!(spans(&string), [(1, 3)]);
+    }
+
+    #[test]
+    fn replace_within_annotation_resizes_it() {
+        let mut string = annotated("abcdef", &[(1, 5)]);
+        string.replace(2, 3, "XYZ");
+        assert_eq!(string.to_string(), "abXYZdef");
+        assert_eq!(spans(&string), [(1, 7)]);
+
+        string.replace(2, 5, "");
+        assert_eq!(string.to_string(), "abdef");
+        assert_eq!(spans(&string), [(1, 4)]);
+    }
+
+    #[test]
+    fn replace_overlapping_annotation_snaps_to_replacement() {
+        // The start is inside the replaced range, so it snaps to its start.
+        let mut string = annotated("abcdef", &[(2, 5)]);
+        string.replace(1, 3, "→");
+        assert_eq!(string.to_string(), "a→def");
+        assert_eq!(spans(&string), [(1, 6)]);
+
+        // The end is inside the replaced range, so it snaps to the end of
+        // the multi-byte replacement rather than into it.
+        let mut string = annotated("abcdef", &[(0, 2)]);
+        string.replace(1, 3, "→");
+        assert_eq!(string.to_string(), "a→def");
+        assert_eq!(spans(&string), [(0, 4)]);
+    }
+
+    #[test]
+    fn shrinking_annotation_to_zero_removes_it() {
+        let mut string = annotated("abcdef", &[(2, 4), (4, 6)]);
+        string.replace(1, 5, "");
+        assert_eq!(string.to_string(), "af");
+        assert_eq!(spans(&string), [(1, 2)]);
+
+        string.truncate_right_from(1);
+        assert_eq!(string.to_string(), "a");
+        assert!(spans(&string).is_empty());
+    }
+
+    #[test]
+    fn growth_never_pushes_annotations_past_end() {
+        let mut string = annotated("abc", &[(1, 3), (2, 10)]);
+        string.replace(0, 1, "XYZ");
+        assert_eq!(string.to_string(), "XYZbc");
+        assert_eq!(spans(&string), [(3, 5), (4, 5)]);
+
+        string.truncate_left_until(4);
+        assert_eq!(string.to_string(), "c");
+        assert_eq!(spans(&string), [(0, 1), (0, 1)]);
+    }
+}
@@ -5,4 +5,18 @@ pub enum AnnotationType {
 
     SelectedMatch,
     Digit,
+    Heading,
+    Bold,
+    Italic,
+    Code,
+    Error,
+    Warning,
+    TrailingWhitespace,
+    MatchingBracket,
+    UnmatchedBracket,
+    WordOccurrence,
+    DiffAdded,
+    DiffChanged,
+    DiffRemoved,
+    Clip,
 }
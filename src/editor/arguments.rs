@@ -0,0 +1,97 @@
+use super::{SearchScroll, command::key_bindings};
+use crate::prelude::*;
+
+const OPTIONS: &str = "\
+Options:
+  -                        Edit standard input in an unnamed buffer
+  --stdout                 Print the buffer to standard output on quit
+  --lossy                  Open invalid UTF-8 read-only, replacing bad bytes
+  --strict                 Exit with an error if the file cannot be opened
+  --check                  Report whitespace issues without opening the editor
+  --minimal-search-scroll  Only scroll when the next match is off screen
+  -h, --help               Print this help and exit
+  -V, --version            Print the version and exit
+  --                       Treat every later argument as a file name
+";
+
+#[derive(Default, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Arguments {
+    /// The files to edit, in order; the first one that opens is shown.
+    pub file_names: Vec<String>,
+    pub read_stdin: bool,
+    pub write_stdout: bool,
+    pub lossy: bool,
+    pub strict: bool,
+    pub check: bool,
+    pub help: bool,
+    pub version: bool,
+    pub search_scroll: SearchScroll,
+    /// The first argument that looked like an option but is not one.
+    pub unknown_option: Option<String>,
+}
+
+impl Arguments {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut arguments = Self::default();
+        let mut options_ended = false;
+        for arg in args.into_iter().skip(1) {
+            if options_ended {
+                if !arguments.read_stdin {
+                    arguments.file_names.push(arg);
+                }
+                continue;
+            }
+            match arg.as_str() {
+                "--" => options_ended = true,
+                "--strict" => arguments.strict = true,
+                "--check" => arguments.check = true,
+                "-h" | "--help" => arguments.help = true,
+                "-V" | "--version" => arguments.version = true,
+                "--minimal-search-scroll" => arguments.search_scroll = SearchScroll::Minimal,
+                "--stdout" => arguments.write_stdout = true,
+                "--lossy" => arguments.lossy = true,
+                "-" if arguments.file_names.is_empty() => arguments.read_stdin = true,
+                _ if arg.starts_with('-') && arg != "-" => {
+                    arguments.unknown_option.get_or_insert(arg);
+                },
+                _ if !arguments.read_stdin => arguments.file_names.push(arg),
+                _ => {},
+            }
+        }
+        arguments
+    }
+
+    pub fn usage() -> String {
+        format!(
+            "Usage: {NAME} [OPTIONS] [FILE[:LINE[:COLUMN]]... | -]\n\n{OPTIONS}\nKey bindings:\n{}",
+            key_bindings()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Arguments {
+        let args = ["hecto"].iter().chain(args).map(ToString::to_string);
+        Arguments::parse(args)
+    }
+
+    #[test]
+    fn unknown_options_are_not_file_names() {
+        let arguments = parse(&["--strcit", "a.txt", "-x"]);
+        assert_eq!(arguments.unknown_option.as_deref(), Some("--strcit"));
+        assert_eq!(arguments.file_names, ["a.txt"]);
+    }
+
+    #[test]
+    fn double_dash_ends_the_options() {
+        let arguments = parse(&["--stdout", "--", "--stdout", "-"]);
+        assert!(arguments.write_stdout);
+        assert!(!arguments.read_stdin);
+        assert_eq!(arguments.unknown_option, None);
+        assert_eq!(arguments.file_names, ["--stdout", "-"]);
+    }
+}
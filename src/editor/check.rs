@@ -0,0 +1,45 @@
+use std::io::ErrorKind;
+
+use super::View;
+
+pub enum CheckOutcome {
+    Clean,
+    IssuesFound,
+    Failed,
+}
+
+impl CheckOutcome {
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            Self::Clean => 0,
+            Self::IssuesFound => 1,
+            Self::Failed => 2,
+        }
+    }
+}
+
+/// Runs the load-time lints on `file_name` without starting the editor UI,
+/// reporting findings on stderr.
+pub fn check_file(file_name: &str) -> CheckOutcome {
+    let mut view = View::default();
+    if let Err(err) = view.load(file_name) {
+        if err.kind() == ErrorKind::InvalidData {
+            eprintln!("{file_name}: invalid UTF-8");
+            return CheckOutcome::IssuesFound;
+        }
+        eprintln!("{file_name}: could not read file: {err}");
+        return CheckOutcome::Failed;
+    }
+    let issues = view.analyze_whitespace();
+    if issues.mixed_indentation {
+        eprintln!("{file_name}: mixed indentation");
+    }
+    if issues.trailing_whitespace {
+        eprintln!("{file_name}: trailing whitespace");
+    }
+    if issues.mixed_indentation || issues.trailing_whitespace {
+        CheckOutcome::IssuesFound
+    } else {
+        CheckOutcome::Clean
+    }
+}
@@ -1,6 +1,6 @@
 
 use crossterm::event::{
-    KeyCode::{Backspace, Char, Delete, Enter, Tab},
+    KeyCode::{Backspace, Char, Delete, Down, Enter, Tab, Up},
     KeyEvent, KeyModifiers,
 };
 
@@ -8,8 +8,49 @@ use crossterm::event::{
 pub enum Edit {
     Insert(char),
     InsertNewline,
+    /// Delete: removes the grapheme at the caret, or joins the next line.
     Delete,
+    /// Backspace: removes the grapheme before the caret, or joins the
+    /// previous line.
     DeleteBackward,
+    KillLine,
+    Transpose,
+    MoveLineUp,
+    MoveLineDown,
+    /// Starts a new line below the current one, like vim's `o`.
+    OpenLineBelow,
+    /// Starts a new line above the current one, like vim's `O`.
+    OpenLineAbove,
+    /// Changes the case of the word at the caret.
+    ChangeCase(CaseChange),
+}
+
+#[derive(Clone, Copy)]
+pub enum CaseChange {
+    Upper,
+    Lower,
+    Toggle,
+}
+
+impl CaseChange {
+    /// Maps character by character, so the result can be longer than
+    /// `text`, e.g. `ß` becomes `SS`.
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Self::Upper => text.to_uppercase(),
+            Self::Lower => text.to_lowercase(),
+            Self::Toggle => text
+                .chars()
+                .flat_map(|character| {
+                    if character.is_lowercase() {
+                        character.to_uppercase().collect::<Vec<_>>()
+                    } else {
+                        character.to_lowercase().collect()
+                    }
+                })
+                .collect(),
+        }
+    }
 }
 
 impl TryFrom<KeyEvent> for Edit {
@@ -21,6 +62,15 @@ impl TryFrom<KeyEvent> for Edit {
                 Ok(Self::Insert(character))
             },
             (Tab, KeyModifiers::NONE) => Ok(Self::Insert('\t')),
+            (Char('k'), KeyModifiers::CONTROL) => Ok(Self::KillLine),
+            (Char('t'), KeyModifiers::CONTROL) => Ok(Self::Transpose),
+            (Up, KeyModifiers::ALT) => Ok(Self::MoveLineUp),
+            (Down, KeyModifiers::ALT) => Ok(Self::MoveLineDown),
+            (Char('o'), KeyModifiers::ALT) => Ok(Self::OpenLineBelow),
+            (Char('a'), KeyModifiers::ALT) => Ok(Self::OpenLineAbove),
+            (Char('u'), KeyModifiers::ALT) => Ok(Self::ChangeCase(CaseChange::Upper)),
+            (Char('l'), KeyModifiers::ALT) => Ok(Self::ChangeCase(CaseChange::Lower)),
+            (Char('c'), KeyModifiers::ALT) => Ok(Self::ChangeCase(CaseChange::Toggle)),
             (Enter, KeyModifiers::NONE) => Ok(Self::InsertNewline),
             (Delete, KeyModifiers::NONE) => Ok(Self::Delete),
             (Backspace, KeyModifiers::NONE) => Ok(Self::DeleteBackward),
@@ -31,3 +81,18 @@ impl TryFrom<KeyEvent> for Edit {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit_for(code: crossterm::event::KeyCode) -> Result<Edit, String> {
+        Edit::try_from(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn backspace_and_delete_keep_their_directions() {
+        assert!(matches!(edit_for(Backspace), Ok(Edit::DeleteBackward)));
+        assert!(matches!(edit_for(Delete), Ok(Edit::Delete)));
+    }
+}
@@ -0,0 +1,24 @@
+use crossterm::event::{
+    KeyCode::{Backspace, Char, Enter, Esc, Tab},
+    KeyEvent, KeyModifiers,
+};
+
+/// The character a key stands for when inserted literally, with Ctrl-letter
+/// combinations mapped to their ASCII control characters.
+pub fn literal_char(key_event: KeyEvent) -> Option<char> {
+    match (key_event.code, key_event.modifiers) {
+        (Char(character), KeyModifiers::CONTROL) => {
+            let byte = u8::try_from(character.to_ascii_uppercase()).ok()?;
+            (b'@'..=b'_')
+                .contains(&byte)
+                .then(|| char::from(byte & 0x1f))
+                .or_else(|| (byte == b' ').then_some('\0'))
+        },
+        (Char(character), KeyModifiers::NONE | KeyModifiers::SHIFT) => Some(character),
+        (Tab, KeyModifiers::NONE) => Some('\t'),
+        (Enter, KeyModifiers::NONE) => Some('\r'),
+        (Esc, KeyModifiers::NONE) => Some('\u{1b}'),
+        (Backspace, KeyModifiers::NONE) => Some('\u{7f}'),
+        _ => None,
+    }
+}
@@ -1,16 +1,20 @@
 
 use crate::prelude::*;
 
-use std::{convert::TryFrom, usize};
+use std::convert::TryFrom;
 
 use crossterm::event::Event;
 
 mod edit;
+mod literal;
 mod move_command;
+mod named;
 mod system;
 
-pub use edit::Edit;
+pub use edit::{CaseChange, Edit};
+pub use literal::literal_char;
 pub use move_command::Move;
+pub use named::{help_text, key_bindings, named_commands};
 pub use system::System;
 
 #[derive(Clone, Copy)]
@@ -25,16 +29,18 @@ impl TryFrom<Event> for Command {
 
     fn try_from(value: Event) -> Result<Self, Self::Error> {
         match value {
+            // Move is tried before System, so a CONTROL combination claimed by
+            // Move (like Ctrl-A) must not also be bound in System.
             Event::Key(key_event) => Edit::try_from(key_event)
                 .map(Command::Edit)
                 .or_else(|_| Move::try_from(key_event).map(Command::Move))
                 .or_else(|_| System::try_from(key_event).map(Command::System))
-                .map_err(|_err| format!("Event not supported: {:?}", key_event)),
+                .map_err(|_err| format!("Event not supported: {key_event:?}")),
             Event::Resize(width_u16, height_u16) => Ok(Self::System(System::Resize(Size {
-                height: height_u16 as usize,
-                width: width_u16 as usize,
+                height: usize::from(height_u16),
+                width: usize::from(width_u16),
             }))),
-            _ => Err(format!("Event not supported: {:?}", value)),
+            _ => Err(format!("Event not supported: {value:?}")),
         }
     }
 }
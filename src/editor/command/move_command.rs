@@ -1,10 +1,10 @@
 
 use crossterm::event::{
-    KeyCode::{Down, End, Home, Left, PageDown, PageUp, Right, Up},
+    KeyCode::{Char, Down, End, Home, Left, PageDown, PageUp, Right, Up},
     KeyEvent, KeyModifiers,
 };
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Move {
     PageUp,
     PageDown,
@@ -14,6 +14,10 @@ pub enum Move {
     Right,
     Up,
     Down,
+    ParagraphUp,
+    ParagraphDown,
+    StartOfFile,
+    EndOfFile,
 }
 
 impl TryFrom<KeyEvent> for Move {
@@ -36,6 +40,24 @@ impl TryFrom<KeyEvent> for Move {
                 Down => Ok(Self::Down),
                 _ => Err(format!("Unsupported code: {code:?}")),
             }
+        } else if modifiers == KeyModifiers::CONTROL {
+            match code {
+                Up => Ok(Self::ParagraphUp),
+                Down => Ok(Self::ParagraphDown),
+                Char('a') => Ok(Self::StartOfLine),
+                Char('e') => Ok(Self::EndOfLine),
+                _ => Err(format!("Unsupported CONTROL+{code:?} combination")),
+            }
+        } else if modifiers == KeyModifiers::ALT
+            || modifiers == KeyModifiers::ALT | KeyModifiers::SHIFT
+        {
+            // Terminals send Alt-<key> as ESC followed by the key; whether
+            // Shift is reported for '<' and '>' depends on the terminal.
+            match code {
+                Char('<') => Ok(Self::StartOfFile),
+                Char('>') => Ok(Self::EndOfFile),
+                _ => Err(format!("Unsupported ALT+{code:?} combination")),
+            }
         } else {
             Err(format!(
                 "Unsupported key code {code:?} or modifier {modifiers:?}"
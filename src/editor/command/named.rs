@@ -0,0 +1,134 @@
+use std::fmt::Write;
+
+use super::{CaseChange, Command, Edit, Move, System};
+
+#[derive(Clone, Copy)]
+pub struct NamedCommand {
+    pub name: &'static str,
+    pub keys: &'static str,
+    pub command: Command,
+}
+
+const fn named(name: &'static str, keys: &'static str, command: Command) -> NamedCommand {
+    NamedCommand {
+        name,
+        keys,
+        command,
+    }
+}
+
+/// The actions offered by the command palette and listed in the help
+/// screen, in display order. `keys` must match the bindings in the
+/// `TryFrom<KeyEvent>` impls.
+pub fn named_commands() -> Vec<NamedCommand> {
+    vec![
+        named("Open file", "Ctrl-O", Command::System(System::Open)),
+        named("Open recent file", "F2", Command::System(System::OpenRecent)),
+        named("Next file", "", Command::System(System::NextFile)),
+        named("Alternate file", "F4", Command::System(System::AlternateFile)),
+        named("Save", "Ctrl-S", Command::System(System::Save)),
+        named("Export as HTML", "", Command::System(System::ExportHtml)),
+        named("Diff against disk", "", Command::System(System::DiffWithDisk)),
+        named(
+            "Toggle change gutter",
+            "",
+            Command::System(System::ToggleChangeGutter),
+        ),
+        named("Find", "Ctrl-F", Command::System(System::Search)),
+        named("File info", "Ctrl-G", Command::System(System::FileInfo)),
+        named("Command palette", "Ctrl-P", Command::System(System::CommandPalette)),
+        named("Help", "F1", Command::System(System::Help)),
+        named("Set mark", "Ctrl-B", Command::System(System::SetMark)),
+        named("Jump to mark", "Ctrl-J", Command::System(System::JumpToMark)),
+        named("Go to line and column", "Ctrl-L", Command::System(System::GoTo)),
+        named("Jump back", "Alt-Left", Command::System(System::JumpBack)),
+        named("Jump forward", "Alt-Right", Command::System(System::JumpForward)),
+        named("Start of line", "Home, Ctrl-A", Command::Move(Move::StartOfLine)),
+        named("End of line", "End, Ctrl-E", Command::Move(Move::EndOfLine)),
+        named("Previous paragraph", "Ctrl-Up", Command::Move(Move::ParagraphUp)),
+        named("Next paragraph", "Ctrl-Down", Command::Move(Move::ParagraphDown)),
+        named("Start of file", "Alt-<", Command::Move(Move::StartOfFile)),
+        named("End of file", "Alt->", Command::Move(Move::EndOfFile)),
+        named(
+            "Squeeze blank lines",
+            "",
+            Command::System(System::SqueezeBlankLines),
+        ),
+        named("Tabs to spaces", "", Command::System(System::TabsToSpaces)),
+        named("Spaces to tabs", "", Command::System(System::SpacesToTabs)),
+        named("Kill to end of line", "Ctrl-K", Command::Edit(Edit::KillLine)),
+        named("Transpose characters", "Ctrl-T", Command::Edit(Edit::Transpose)),
+        named("Move line up", "Alt-Up", Command::Edit(Edit::MoveLineUp)),
+        named("Move line down", "Alt-Down", Command::Edit(Edit::MoveLineDown)),
+        named("Open line below", "Alt-O", Command::Edit(Edit::OpenLineBelow)),
+        named("Open line above", "Alt-A", Command::Edit(Edit::OpenLineAbove)),
+        named(
+            "Uppercase word",
+            "Alt-U",
+            Command::Edit(Edit::ChangeCase(CaseChange::Upper)),
+        ),
+        named(
+            "Lowercase word",
+            "Alt-L",
+            Command::Edit(Edit::ChangeCase(CaseChange::Lower)),
+        ),
+        named(
+            "Toggle case of word",
+            "Alt-C",
+            Command::Edit(Edit::ChangeCase(CaseChange::Toggle)),
+        ),
+        named("Repeat last edit", "Alt-.", Command::System(System::RepeatEdit)),
+        named("Insert next key literally", "Ctrl-V", Command::System(System::QuotedInsert)),
+        named(
+            "Next trailing whitespace",
+            "F8",
+            Command::System(System::NextAnnotation),
+        ),
+        named(
+            "Previous trailing whitespace",
+            "Shift-F8",
+            Command::System(System::PrevAnnotation),
+        ),
+        #[cfg(unix)]
+        named("Suspend", "Ctrl-Z", Command::System(System::Suspend)),
+        named("Save and quit", "Ctrl-X", Command::System(System::SaveAndQuit)),
+        named("Quit", "Ctrl-Q", Command::System(System::Quit)),
+    ]
+}
+
+/// The contents of the help screen.
+pub fn help_text() -> String {
+    let mut text = String::from(
+        "Hecto help (Esc or F1 to close)\n\
+         \n\
+         Arrows, Page Up/Down move the cursor. Type to insert text;\n\
+         Enter, Backspace and Delete work as usual. Tab completes file\n\
+         names in the open and save prompts.\n\
+         \n\
+         Alt and a number before a command repeats it, e.g. Alt-5 Down\n\
+         moves down five lines and Alt-3 - inserts ---.\n\
+         \n",
+    );
+    text.push_str(&key_bindings());
+    text
+}
+
+/// One line per named command with its keys; palette-only commands are
+/// listed by name.
+pub fn key_bindings() -> String {
+    let commands = named_commands();
+    let name_width = commands
+        .iter()
+        .map(|named| named.name.len())
+        .max()
+        .unwrap_or(0);
+    let mut text = String::new();
+    for named in commands {
+        if named.keys.is_empty() {
+            let _ = writeln!(text, "{}", named.name);
+        } else {
+            let _ = writeln!(text, "{:<name_width$}  {}", named.name, named.keys);
+        }
+    }
+    text
+}
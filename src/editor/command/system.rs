@@ -11,8 +11,46 @@ pub enum System {
     Resize(Size),
     Save,
     Quit,
+    /// Saves, asking for a name if there is none, and quits once saved.
+    SaveAndQuit,
     Dismiss,
     Search,
+    SetMark,
+    JumpToMark,
+    Open,
+    /// Asks for the number of a file listed on the welcome screen.
+    OpenRecent,
+    /// Only reachable from the command palette.
+    NextFile,
+    AlternateFile,
+    FileInfo,
+    GoTo,
+    /// Only reachable from the command palette.
+    ExportHtml,
+    /// Only reachable from the command palette.
+    SqueezeBlankLines,
+    /// Only reachable from the command palette.
+    TabsToSpaces,
+    /// Only reachable from the command palette.
+    SpacesToTabs,
+    /// Only reachable from the command palette.
+    DiffWithDisk,
+    /// Only reachable from the command palette.
+    ToggleChangeGutter,
+    CommandPalette,
+    Help,
+    NextAnnotation,
+    PrevAnnotation,
+    JumpBack,
+    JumpForward,
+    /// One digit of a repeat count, typed with Alt so plain digits still
+    /// insert text.
+    Count(u32),
+    RepeatEdit,
+    QuotedInsert,
+    Interrupt,
+    #[cfg(unix)]
+    Suspend,
 }
 
 impl TryFrom<KeyEvent> for System {
@@ -27,11 +65,46 @@ impl TryFrom<KeyEvent> for System {
             match code {
                 Char('q') => Ok(Self::Quit),
                 Char('s') => Ok(Self::Save),
+                Char('x') => Ok(Self::SaveAndQuit),
                 Char('f') => Ok(Self::Search),
+                Char('b') => Ok(Self::SetMark),
+                Char('j') => Ok(Self::JumpToMark),
+                Char('o') => Ok(Self::Open),
+                Char('g') => Ok(Self::FileInfo),
+                Char('l') => Ok(Self::GoTo),
+                Char('p') => Ok(Self::CommandPalette),
+                Char('v') => Ok(Self::QuotedInsert),
+                Char('c') => Ok(Self::Interrupt),
+                #[cfg(unix)]
+                Char('z') => Ok(Self::Suspend),
                 _ => Err(format!("Unsupported CONTROL+{code:?} combination")),
             }
+        } else if modifiers == KeyModifiers::ALT
+            && let Char(character) = code
+        {
+            match character {
+                '.' => Ok(Self::RepeatEdit),
+                _ => character
+                    .to_digit(10)
+                    .map(Self::Count)
+                    .ok_or_else(|| format!("Unsupported ALT+{code:?} combination")),
+            }
+        } else if modifiers == KeyModifiers::ALT && code == KeyCode::Left {
+            Ok(Self::JumpBack)
+        } else if modifiers == KeyModifiers::ALT && code == KeyCode::Right {
+            Ok(Self::JumpForward)
         } else if modifiers == KeyModifiers::NONE && matches!(code, KeyCode::Esc) {
             Ok(Self::Dismiss)
+        } else if code == KeyCode::F(1) && modifiers == KeyModifiers::NONE {
+            Ok(Self::Help)
+        } else if code == KeyCode::F(2) && modifiers == KeyModifiers::NONE {
+            Ok(Self::OpenRecent)
+        } else if code == KeyCode::F(4) && modifiers == KeyModifiers::NONE {
+            Ok(Self::AlternateFile)
+        } else if code == KeyCode::F(8) && modifiers == KeyModifiers::NONE {
+            Ok(Self::NextAnnotation)
+        } else if code == KeyCode::F(8) && modifiers == KeyModifiers::SHIFT {
+            Ok(Self::PrevAnnotation)
         } else {
             Err(format!(
                 "Unsupported key code {code:?} or modifier {modifiers:?}"
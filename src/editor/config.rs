@@ -0,0 +1,359 @@
+use std::{env, fs, path::PathBuf, time::Duration};
+
+use super::{FileType, LineEnding, SearchCase, SearchScroll, alternate_file};
+
+const CONFIG_FILE_NAME: &str = "config";
+const DEFAULT_TAB_WIDTH: usize = 4;
+const DEFAULT_CENTER_PERCENT: usize = 50;
+/// Settings of the editor rather than of a buffer, so they can only be set
+/// before the first section.
+const GLOBAL_ONLY_KEYS: [&str; 3] = ["status_format", "message_duration", "alternate_files"];
+
+/// The settings in effect for one buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct EditorConfig {
+    pub tab_width: usize,
+    pub expand_tabs: bool,
+    pub show_tabs: bool,
+    pub scroll_margin: usize,
+    pub search_scroll: SearchScroll,
+    pub search_case: SearchCase,
+    /// Where centering puts the caret, in percent of the view height from
+    /// the top. Set as `center_ratio`, a fraction between 0 and 1.
+    pub center_percent: usize,
+    /// Drop typed or pasted control characters other than tab and newline.
+    pub filter_control_chars: bool,
+    /// Mark the other occurrences of the word under the caret.
+    pub highlight_word: bool,
+    /// Mark whitespace at the end of lines, on screen and in HTML exports.
+    pub highlight_trailing_whitespace: bool,
+    /// Drawn on the rows past the end of the buffer; `None` leaves them
+    /// blank.
+    pub filler: Option<char>,
+    /// Show signs for lines changed since the file was loaded or saved.
+    pub change_gutter: bool,
+    /// Tabs to spaces also converts tabs after the indentation.
+    pub convert_all_tabs: bool,
+    /// Held Up or Down moves further with each repeat.
+    pub move_acceleration: bool,
+    /// Saving drops the whitespace at the end of every line.
+    pub trim_trailing_whitespace: bool,
+    /// Saving ends the file with a line break, or without one; `None`
+    /// keeps what the file had.
+    pub insert_final_newline: Option<bool>,
+    /// The line break to save with; `None` keeps the file's own.
+    pub line_ending: Option<LineEnding>,
+}
+
+impl Default for EditorConfig {
+    fn default() -> Self {
+        Self {
+            tab_width: DEFAULT_TAB_WIDTH,
+            expand_tabs: false,
+            show_tabs: false,
+            scroll_margin: 0,
+            search_scroll: SearchScroll::default(),
+            search_case: SearchCase::default(),
+            center_percent: DEFAULT_CENTER_PERCENT,
+            filter_control_chars: true,
+            highlight_word: false,
+            highlight_trailing_whitespace: true,
+            filler: Some('~'),
+            change_gutter: false,
+            convert_all_tabs: false,
+            move_acceleration: false,
+            trim_trailing_whitespace: false,
+            insert_final_newline: None,
+            line_ending: None,
+        }
+    }
+}
+
+impl EditorConfig {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let invalid = || format!("invalid value '{value}' for '{key}'");
+        match key {
+            "tab_width" => {
+                self.tab_width = value
+                    .parse()
+                    .ok()
+                    .filter(|&width| width > 0)
+                    .ok_or_else(invalid)?;
+            },
+            "expand_tabs" => self.expand_tabs = value.parse().map_err(|_| invalid())?,
+            "show_tabs" => self.show_tabs = value.parse().map_err(|_| invalid())?,
+            "filter_control_chars" => {
+                self.filter_control_chars = value.parse().map_err(|_| invalid())?;
+            },
+            "change_gutter" => self.change_gutter = value.parse().map_err(|_| invalid())?,
+            "convert_all_tabs" => {
+                self.convert_all_tabs = value.parse().map_err(|_| invalid())?;
+            },
+            "move_acceleration" => {
+                self.move_acceleration = value.parse().map_err(|_| invalid())?;
+            },
+            "trim_trailing_whitespace" => {
+                self.trim_trailing_whitespace = value.parse().map_err(|_| invalid())?;
+            },
+            "insert_final_newline" => {
+                self.insert_final_newline = Some(value.parse().map_err(|_| invalid())?);
+            },
+            "line_ending" => {
+                self.line_ending = Some(match value {
+                    "lf" => LineEnding::Lf,
+                    "crlf" => LineEnding::CrLf,
+                    _ => return Err(invalid()),
+                });
+            },
+            "highlight_word" => self.highlight_word = value.parse().map_err(|_| invalid())?,
+            "highlight_trailing_whitespace" => {
+                self.highlight_trailing_whitespace = value.parse().map_err(|_| invalid())?;
+            },
+            "scroll_margin" => self.scroll_margin = value.parse().map_err(|_| invalid())?,
+            "search_scroll" => {
+                self.search_scroll = match value {
+                    "center" => SearchScroll::Center,
+                    "minimal" => SearchScroll::Minimal,
+                    _ => return Err(invalid()),
+                };
+            },
+            "search_case" => {
+                self.search_case = match value {
+                    "smart" => SearchCase::Smart,
+                    "sensitive" => SearchCase::Sensitive,
+                    "insensitive" => SearchCase::Insensitive,
+                    _ => return Err(invalid()),
+                };
+            },
+            "filler" => {
+                let mut chars = value.chars();
+                self.filler = match (chars.next(), chars.next()) {
+                    (filler, None) => filler,
+                    _ => return Err(invalid()),
+                };
+            },
+            "center_ratio" => {
+                let ratio = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|ratio| (0.0..=1.0).contains(ratio))
+                    .ok_or_else(invalid)?;
+                #[allow(
+                    clippy::as_conversions,
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss
+                )]
+                let percent = (ratio * 100.0).round() as usize;
+                self.center_percent = percent;
+            },
+            _ => return Err(format!("unknown key '{key}'")),
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, settings: &[(String, String)]) {
+        for (key, value) in settings {
+            let _ = self.set(key, value);
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/hecto/config`: one `key = value` per line, blank lines
+/// ignored. A `#` at the start of a line or after whitespace starts a
+/// comment. Keys before the first section are
+/// global; a `[rust]`, `[markdown]` or `[text]` section overrides them for
+/// that file type.
+#[derive(Default)]
+pub struct ConfigFile {
+    global: EditorConfig,
+    sections: Vec<(FileType, Vec<(String, String)>)>,
+    overrides: Vec<(String, String)>,
+    status_format: Option<String>,
+    message_duration: Option<Duration>,
+    alternate_files: Option<Vec<(String, String)>>,
+}
+
+impl ConfigFile {
+    /// Loads the config file, falling back to defaults if it is missing.
+    /// Problems with individual lines are returned as warnings instead of
+    /// aborting, so one typo doesn't discard the rest of the file.
+    pub fn load() -> (Self, Vec<String>) {
+        Self::config_file()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map_or_else(|| (Self::default(), Vec::new()), |contents| Self::parse(&contents))
+    }
+
+    pub fn parse(contents: &str) -> (Self, Vec<String>) {
+        let mut config_file = Self::default();
+        let mut warnings = Vec::new();
+        // Settings of unknown sections are still checked, then dropped.
+        let mut section: Option<Option<FileType>> = None;
+        for (idx, line) in contents.lines().enumerate() {
+            let line_number = idx.saturating_add(1);
+            let line = Self::strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                let file_type = Self::section_file_type(name.trim());
+                if file_type.is_none() {
+                    warnings.push(format!("unknown section '{name}' on line {line_number}"));
+                }
+                section = Some(file_type);
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                warnings.push(format!("expected 'key = value' on line {line_number}"));
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let result = match section {
+                None if GLOBAL_ONLY_KEYS.contains(&key) => config_file.set_global(key, value),
+                None => config_file.global.set(key, value),
+                Some(_) if GLOBAL_ONLY_KEYS.contains(&key) => {
+                    Err(format!("'{key}' is only valid before the first section"))
+                },
+                Some(file_type) => EditorConfig::default().set(key, value).map(|()| {
+                    if let Some(file_type) = file_type {
+                        config_file.section_mut(file_type).push((key.into(), value.into()));
+                    }
+                }),
+            };
+            if let Err(warning) = result {
+                warnings.push(format!("{warning} on line {line_number}"));
+            }
+        }
+        (config_file, warnings)
+    }
+
+    /// Cuts `line` at the first `#` that starts it or follows whitespace,
+    /// so values like `a#b` keep theirs.
+    fn strip_comment(line: &str) -> &str {
+        let mut previous: Option<char> = None;
+        for (idx, character) in line.char_indices() {
+            if character == '#' && previous.is_none_or(char::is_whitespace) {
+                return line.get(..idx).unwrap_or(line);
+            }
+            previous = Some(character);
+        }
+        line
+    }
+
+    fn set_global(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "status_format" => self.status_format = Some(value.into()),
+            "message_duration" => {
+                let seconds = value
+                    .parse()
+                    .ok()
+                    .filter(|&seconds| seconds > 0)
+                    .ok_or_else(|| format!("invalid value '{value}' for '{key}'"))?;
+                self.message_duration = Some(Duration::from_secs(seconds));
+            },
+            "alternate_files" => {
+                let rules = alternate_file::parse_rules(value)
+                    .ok_or_else(|| format!("invalid value '{value}' for '{key}'"))?;
+                self.alternate_files = Some(rules);
+            },
+            _ => return Err(format!("unknown key '{key}'")),
+        }
+        Ok(())
+    }
+
+    /// Sets a value that wins over both global and file type settings, as
+    /// command line flags should.
+    pub fn set_override(&mut self, key: &str, value: &str) {
+        self.overrides.push((key.into(), value.into()));
+    }
+
+    pub fn status_format(&self) -> Option<&str> {
+        self.status_format.as_deref()
+    }
+
+    pub fn message_duration(&self) -> Option<Duration> {
+        self.message_duration
+    }
+
+    pub fn alternate_files(&self) -> Option<&[(String, String)]> {
+        self.alternate_files.as_deref()
+    }
+
+    /// `project` holds the settings from `.editorconfig` files, which win
+    /// over this file but not over command line flags.
+    pub fn effective(&self, file_type: FileType, project: &[(String, String)]) -> EditorConfig {
+        let mut config = self.global;
+        if let Some((_, settings)) = self.sections.iter().find(|(ty, _)| *ty == file_type) {
+            config.apply(settings);
+        }
+        config.apply(project);
+        config.apply(&self.overrides);
+        config
+    }
+
+    fn section_mut(&mut self, file_type: FileType) -> &mut Vec<(String, String)> {
+        let idx = self
+            .sections
+            .iter()
+            .position(|(ty, _)| *ty == file_type)
+            .unwrap_or_else(|| {
+                self.sections.push((file_type, Vec::new()));
+                self.sections.len().saturating_sub(1)
+            });
+        #[allow(clippy::indexing_slicing)]
+        &mut self.sections[idx].1
+    }
+
+    fn section_file_type(name: &str) -> Option<FileType> {
+        match name {
+            "rust" => Some(FileType::Rust),
+            "markdown" => Some(FileType::Markdown),
+            "text" => Some(FileType::PlainText),
+            _ => None,
+        }
+    }
+
+    fn config_file() -> Option<PathBuf> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_dir.join("hecto").join(CONFIG_FILE_NAME))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example config in the README, between the fences after the
+    /// Configuration heading.
+    fn readme_example() -> &'static str {
+        let readme = include_str!("../../README.md");
+        let section = &readme[readme.find("### Configuration").unwrap()..];
+        let start = section.find("```\n").unwrap().saturating_add(4);
+        let end = section[start..].find("```").unwrap();
+        &section[start..start.saturating_add(end)]
+    }
+
+    #[test]
+    fn readme_example_parses_cleanly() {
+        let (config_file, warnings) = ConfigFile::parse(readme_example());
+        assert_eq!(warnings, Vec::<String>::new());
+        assert_eq!(config_file.status_format(), Some("%f - %L lines %m%=%r%t | %l/%L"));
+        assert_eq!(config_file.message_duration(), Some(Duration::from_secs(5)));
+        assert_eq!(config_file.alternate_files().map(<[_]>::len), Some(4));
+        let rust = config_file.effective(FileType::Rust, &[]);
+        assert!(rust.expand_tabs);
+        assert_eq!(rust.filler, Some('~'));
+        assert!(!config_file.effective(FileType::PlainText, &[]).expand_tabs);
+    }
+
+    #[test]
+    fn comments_need_whitespace_before_them() {
+        let (config_file, warnings) =
+            ConfigFile::parse("# heading\nstatus_format = a#b # comment\n  # indented\n");
+        assert!(warnings.is_empty());
+        assert_eq!(config_file.status_format(), Some("a#b"));
+    }
+}
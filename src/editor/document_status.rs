@@ -2,12 +2,18 @@ use super::FileType;
 use crate::prelude::*;
 
 #[derive(Default, PartialEq, Eq, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct DocumentStatus {
     pub total_lines: usize,
     pub current_line_idx: LineIdx,
+    pub current_grapheme_idx: GraphemeIdx,
     pub is_modified: bool,
     pub file_name: String,
     pub file_type: FileType,
+    pub is_symlink: bool,
+    pub is_executable: bool,
+    pub is_read_only: bool,
+    pub is_lossy: bool,
 }
 
 impl DocumentStatus {
@@ -19,15 +25,40 @@ impl DocumentStatus {
         }
     }
 
-    pub fn line_count_to_string(&self) -> String {
-        format!("{} lines", self.total_lines)
+    pub fn file_flags_to_string(&self) -> String {
+        let mut flags = String::new();
+        if self.is_symlink {
+            flags.push_str("[sym]");
+        }
+        if self.is_executable {
+            flags.push_str("[exe]");
+        }
+        flags
+    }
+
+    pub fn modes_to_string(&self) -> String {
+        let mut modes = String::new();
+        if self.is_read_only {
+            modes.push_str("[RO]");
+        }
+        if self.is_lossy {
+            modes.push_str("[lossy]");
+        }
+        modes
+    }
+
+    pub fn column_to_string(&self) -> String {
+        self.current_grapheme_idx.saturating_add(1).to_string()
     }
 
-    pub fn position_indicator_to_string(&self) -> String {
-        format!(
-            "{}/{}",
-            self.current_line_idx.saturating_add(1),
-            self.total_lines
-        )
+    /// How far down the file the caret line is.
+    pub fn percent_to_string(&self) -> String {
+        let percent = self
+            .current_line_idx
+            .saturating_add(1)
+            .saturating_mul(100)
+            .checked_div(self.total_lines)
+            .unwrap_or(100);
+        format!("{percent}%")
     }
 }
@@ -0,0 +1,356 @@
+use std::{
+    fs,
+    path::{self, Path, PathBuf},
+};
+
+const FILE_NAME: &str = ".editorconfig";
+
+/// The settings `.editorconfig` files give the file at `path`, as config
+/// file keys and values. Files are read from the file's directory upwards
+/// until one says `root = true`; closer files win over those further up.
+pub fn settings_for(path: &Path) -> Vec<(String, String)> {
+    let Some(path) = fs::canonicalize(path)
+        .or_else(|_| path::absolute(path))
+        .ok()
+    else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+    for dir in path.ancestors().skip(1) {
+        let Ok(contents) = fs::read_to_string(dir.join(FILE_NAME)) else {
+            continue;
+        };
+        let (root, _) = parse(&contents);
+        files.push((dir.to_path_buf(), contents));
+        if root {
+            break;
+        }
+    }
+    let mut properties = Vec::new();
+    for (dir, contents) in files.iter().rev() {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
+        };
+        // Globs always use `/`, and a leading one anchors them to `dir`.
+        let relative = format!("/{}", relative.to_string_lossy().replace('\\', "/"));
+        let (_, sections) = parse(contents);
+        for (glob, section_properties) in sections {
+            if section_matches(&glob, &relative) {
+                for (key, value) in section_properties {
+                    properties.retain(|(other, _)| *other != key);
+                    if value != "unset" {
+                        properties.push((key, value));
+                    }
+                }
+            }
+        }
+    }
+    translate(&properties)
+}
+
+type Section = (String, Vec<(String, String)>);
+
+/// Whether the file says `root = true`, and its sections. Keys and values
+/// are lowercased, as the properties this editor knows are case-insensitive.
+fn parse(contents: &str) -> (bool, Vec<Section>) {
+    let mut root = false;
+    let mut sections: Vec<Section> = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(glob) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            sections.push((glob.into(), Vec::new()));
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim().to_lowercase(), value.trim().to_lowercase());
+        match sections.last_mut() {
+            Some((_, properties)) => properties.push((key, value)),
+            None if key == "root" => root = value == "true",
+            None => {},
+        }
+    }
+    (root, sections)
+}
+
+/// A glob without a `/` matches the file name in any directory; one with a
+/// `/` matches the path from the `.editorconfig` file's directory.
+fn section_matches(glob: &str, path: &str) -> bool {
+    let pattern = if glob.contains('/') {
+        format!("/{}", glob.trim_start_matches('/'))
+    } else {
+        format!("**/{glob}")
+    };
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = path.chars().collect();
+    glob_matches(&pattern, &path)
+}
+
+/// `*` matches within a path segment and `**` across segments; `?`,
+/// `[set]`, `[!set]`, `{a,b}` and `{1..9}` work as in shells.
+fn glob_matches(pattern: &[char], text: &[char]) -> bool {
+    let Some((&first, rest)) = pattern.split_first() else {
+        return text.is_empty();
+    };
+    match first {
+        '*' => match rest.split_first() {
+            // `a/**/b` also matches `a/b`.
+            Some(('*', ['/', after @ ..])) if glob_matches(after, text) => true,
+            Some(('*', rest)) => (0..=text.len()).any(|idx| glob_matches(rest, &text[idx..])),
+            _ => {
+                let limit = text.iter().position(|&ch| ch == '/').unwrap_or(text.len());
+                (0..=limit).any(|idx| glob_matches(rest, &text[idx..]))
+            },
+        },
+        '?' => match text.split_first() {
+            Some((&ch, text)) => ch != '/' && glob_matches(rest, text),
+            None => false,
+        },
+        '[' if rest.contains(&']') => {
+            let close = rest.iter().position(|&ch| ch == ']').unwrap_or_default();
+            let (set, after) = rest.split_at(close);
+            let (negated, set) = match set.split_first() {
+                Some(('!', set)) => (true, set),
+                _ => (false, set),
+            };
+            match text.split_first() {
+                Some((&ch, text)) => {
+                    ch != '/'
+                        && set_contains(set, ch) != negated
+                        && glob_matches(&after[1..], text)
+                },
+                None => false,
+            }
+        },
+        '{' => match closing_brace(rest) {
+            Some(close) => braces_match(&rest[..close], &rest[close..][1..], text),
+            None => literal_matches('{', rest, text),
+        },
+        '\\' => match rest.split_first() {
+            Some((&escaped, rest)) => literal_matches(escaped, rest, text),
+            None => literal_matches('\\', rest, text),
+        },
+        _ => literal_matches(first, rest, text),
+    }
+}
+
+fn literal_matches(expected: char, rest: &[char], text: &[char]) -> bool {
+    match text.split_first() {
+        Some((&ch, text)) => ch == expected && glob_matches(rest, text),
+        None => false,
+    }
+}
+
+fn set_contains(mut set: &[char], ch: char) -> bool {
+    while let Some((&first, rest)) = set.split_first() {
+        if let ['-', last, tail @ ..] = rest {
+            if (first..=*last).contains(&ch) {
+                return true;
+            }
+            set = tail;
+        } else {
+            if first == ch {
+                return true;
+            }
+            set = rest;
+        }
+    }
+    false
+}
+
+/// The index of the `}` closing a brace whose contents start `pattern`.
+fn closing_brace(pattern: &[char]) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (idx, &ch) in pattern.iter().enumerate() {
+        match ch {
+            '{' => depth = depth.saturating_add(1),
+            '}' if depth == 0 => return Some(idx),
+            '}' => depth = depth.saturating_sub(1),
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Matches `{body}` followed by `after`: any of the comma separated
+/// alternatives, or an integer in a `start..end` range.
+fn braces_match(body: &[char], after: &[char], text: &[char]) -> bool {
+    let mut alternatives = vec![Vec::new()];
+    let mut depth = 0_usize;
+    for &ch in body {
+        match ch {
+            ',' if depth == 0 => alternatives.push(Vec::new()),
+            '{' => depth = depth.saturating_add(1),
+            '}' => depth = depth.saturating_sub(1),
+            _ => {},
+        }
+        if (ch != ',' || depth > 0)
+            && let Some(alternative) = alternatives.last_mut()
+        {
+            alternative.push(ch);
+        }
+    }
+    if alternatives.len() > 1 {
+        return alternatives.iter().any(|alternative| {
+            let pattern: Vec<char> = alternative.iter().chain(after).copied().collect();
+            glob_matches(&pattern, text)
+        });
+    }
+    let body: String = body.iter().collect();
+    let Some((start, end)) = body
+        .split_once("..")
+        .and_then(|(start, end)| Some((start.parse::<i64>().ok()?, end.parse::<i64>().ok()?)))
+    else {
+        // A brace without alternatives or a range is taken literally.
+        let pattern: Vec<char> = body.chars().chain(['}']).chain(after.iter().copied()).collect();
+        return literal_matches('{', &pattern, text);
+    };
+    let sign_len = usize::from(text.first() == Some(&'-'));
+    let digit_count = text[sign_len..].iter().take_while(|ch| ch.is_ascii_digit()).count();
+    let len = sign_len.saturating_add(digit_count);
+    let number: String = text[..len].iter().collect();
+    number
+        .parse::<i64>()
+        .is_ok_and(|number| (start.min(end)..=start.max(end)).contains(&number))
+        && glob_matches(after, &text[len..])
+}
+
+/// Maps the `.editorconfig` properties to the config file keys for them.
+/// Properties the editor has no setting for are ignored.
+fn translate(properties: &[(String, String)]) -> Vec<(String, String)> {
+    let get = |key: &str| {
+        properties
+            .iter()
+            .find(|(other, _)| other == key)
+            .map(|(_, value)| value.as_str())
+    };
+    let mut settings: Vec<(String, String)> = Vec::new();
+    let mut push = |key: &str, value: &str| settings.push((key.into(), value.into()));
+    match get("indent_style") {
+        Some("tab") => push("expand_tabs", "false"),
+        Some("space") => push("expand_tabs", "true"),
+        _ => {},
+    }
+    // There is one width for both, and `indent_size = tab` means the tab
+    // width anyway.
+    let indent_size = get("indent_size").filter(|&size| size != "tab");
+    if let Some(width) = get("tab_width").or(indent_size) {
+        push("tab_width", width);
+    }
+    if let Some(line_ending @ ("lf" | "crlf")) = get("end_of_line") {
+        push("line_ending", line_ending);
+    }
+    for key in ["trim_trailing_whitespace", "insert_final_newline"] {
+        if let Some(value) = get(key) {
+            push(key, value);
+        }
+    }
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::ScratchDir;
+
+    fn matches(glob: &str, path: &str) -> bool {
+        section_matches(glob, path)
+    }
+
+    fn setting<'a>(settings: &'a [(String, String)], key: &str) -> Option<&'a str> {
+        settings
+            .iter()
+            .find(|(other, _)| other == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn star_stays_within_a_directory() {
+        assert!(matches("*.rs", "/main.rs"));
+        assert!(matches("*.rs", "/src/main.rs"));
+        assert!(!matches("*.rs", "/main.rs.bak"));
+        assert!(matches("src/*.rs", "/src/main.rs"));
+        assert!(!matches("src/*.rs", "/src/bin/main.rs"));
+        assert!(!matches("src/*.rs", "/lib/src/main.rs"));
+    }
+
+    #[test]
+    fn double_star_crosses_directories() {
+        assert!(matches("src/**.rs", "/src/bin/main.rs"));
+        assert!(matches("a/**/b", "/a/b"));
+        assert!(matches("a/**/b", "/a/x/y/b"));
+        assert!(!matches("a/**/b", "/ab"));
+        assert!(matches("**/x/*.c", "/x/y.c"));
+    }
+
+    #[test]
+    fn question_mark_and_sets() {
+        assert!(matches("?.c", "/a.c"));
+        assert!(!matches("?.c", "/ab.c"));
+        assert!(!matches("a?b", "/a/b"));
+        assert!(matches("*.[ch]", "/a.h"));
+        assert!(!matches("*.[ch]", "/a.o"));
+        assert!(matches("[a-c].txt", "/b.txt"));
+        assert!(!matches("[!ab].txt", "/b.txt"));
+        assert!(matches("[!ab].txt", "/c.txt"));
+    }
+
+    #[test]
+    fn braces() {
+        assert!(matches("*.{rs,md}", "/a.md"));
+        assert!(!matches("*.{rs,md}", "/a.txt"));
+        assert!(matches("{src,tests}/*.rs", "/tests/a.rs"));
+        assert!(matches("f{1..3}.c", "/f2.c"));
+        assert!(!matches("f{1..3}.c", "/f4.c"));
+        assert!(matches("f{-2..2}.c", "/f-1.c"));
+        assert!(matches("{a}", "/{a}"), "no alternatives is literal");
+    }
+
+    #[test]
+    fn parse_reads_root_and_sections() {
+        let (root, sections) =
+            parse("; comment\nROOT = True\n\n[*.md]\nIndent_Style = Tab\n# comment\n");
+        assert!(root);
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].0, "*.md");
+        assert_eq!(sections[0].1, [("indent_style".into(), "tab".into())]);
+        assert!(!parse("[*]\nroot = true\n").0, "root only counts in the preamble");
+    }
+
+    #[test]
+    fn nearer_files_override_and_root_stops_the_search() {
+        let dir = ScratchDir::new("editorconfig");
+        let project = dir.join("project");
+        let sub = project.join("sub");
+        fs::create_dir_all(&sub).unwrap();
+        fs::write(dir.join(FILE_NAME), "[*]\ninsert_final_newline = true\n").unwrap();
+        fs::write(
+            project.join(FILE_NAME),
+            "root = true\n[*]\nindent_style = space\nindent_size = 2\nend_of_line = crlf\n",
+        )
+        .unwrap();
+        fs::write(
+            sub.join(FILE_NAME),
+            "[*.md]\nindent_size = 8\nend_of_line = unset\n",
+        )
+        .unwrap();
+        let file = sub.join("a.md");
+        fs::write(&file, "").unwrap();
+
+        let settings = settings_for(&file);
+        assert_eq!(setting(&settings, "expand_tabs"), Some("true"));
+        assert_eq!(setting(&settings, "tab_width"), Some("8"), "nearer file wins");
+        assert_eq!(setting(&settings, "line_ending"), None, "unset");
+        assert_eq!(setting(&settings, "insert_final_newline"), None, "above the root");
+
+        let other = sub.join("a.rs");
+        fs::write(&other, "").unwrap();
+        let settings = settings_for(&other);
+        assert_eq!(setting(&settings, "tab_width"), Some("2"));
+        assert_eq!(setting(&settings, "line_ending"), Some("crlf"));
+    }
+}
@@ -0,0 +1,36 @@
+use super::FileType;
+
+const ELLIPSIS: char = '…';
+
+#[derive(Default, PartialEq, Eq, Debug)]
+pub struct FileSummary {
+    pub path: Option<String>,
+    pub size: u64,
+    pub total_lines: usize,
+    pub line_ending: &'static str,
+    pub file_type: FileType,
+}
+
+impl FileSummary {
+    /// Formats the summary for a single line of `max_width` columns,
+    /// shortening the path from the left if the whole line would not fit.
+    pub fn to_message(&self, max_width: usize) -> String {
+        let details = format!(
+            " {} bytes, {} lines, {}, {}",
+            self.size, self.total_lines, self.line_ending, self.file_type
+        );
+        let path = self.path.as_deref().unwrap_or("[No Name]");
+        let available = max_width.saturating_sub(details.chars().count().saturating_add(2));
+        let path_len = path.chars().count();
+        let path = if path_len <= available {
+            path.to_string()
+        } else {
+            let kept: String = path
+                .chars()
+                .skip(path_len.saturating_sub(available.saturating_sub(1)))
+                .collect();
+            format!("{ELLIPSIS}{kept}")
+        };
+        format!("\"{path}\"{details}")
+    }
+}
@@ -4,6 +4,7 @@ pub enum FileType {
     #[default]
     PlainText,
     Rust,
+    Markdown,
 }
 
 impl From<&PathBuf> for FileType {
@@ -11,11 +12,11 @@ impl From<&PathBuf> for FileType {
         path_buf
             .extension()
             .and_then(|ext| ext.to_str())
-            .map(|ext| match ext {
+            .map_or(FileType::PlainText, |ext| match ext {
                 "rs" => FileType::Rust,
+                "md" | "markdown" => FileType::Markdown,
                 _ => FileType::PlainText,
             })
-            .unwrap_or(FileType::PlainText)
     }
 }
 
@@ -23,8 +24,9 @@ impl Display for FileType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let string = match self {
             Self::Rust => "Rust",
-            _ => "Text",
+            Self::Markdown => "Markdown",
+            Self::PlainText => "Text",
         };
-        write!(f, "{}", string)
+        write!(f, "{string}")
     }
 }
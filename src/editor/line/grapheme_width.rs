@@ -3,6 +3,7 @@
 pub enum GraphemeWidth {
     Half,
     Full,
+    Tab(usize),
 }
 
 impl From<GraphemeWidth> for usize {
@@ -10,6 +11,7 @@ impl From<GraphemeWidth> for usize {
         match value {
             GraphemeWidth::Full => 2,
             GraphemeWidth::Half => 1,
+            GraphemeWidth::Tab(columns) => columns,
         }
     }
 }
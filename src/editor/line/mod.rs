@@ -1,4 +1,5 @@
 mod grapheme_width;
+mod tab_style;
 mod text_fragment;
 
 use crate::{editor::annotation::Annotation, prelude::*};
@@ -10,43 +11,65 @@ use std::{
 };
 
 use grapheme_width::GraphemeWidth;
+pub use tab_style::TabStyle;
 use text_fragment::TextFragment;
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
-use super::AnnotatedString;
+use super::{AnnotatedString, AnnotationType};
+
+/// Drawn in place of a grapheme cut off at the edge of the view.
+const CLIP_INDICATOR: char = '⋯';
 
 #[derive(Default, Clone)]
 pub struct Line {
     pub fragments: Vec<TextFragment>,
     string: String,
+    tab_style: TabStyle,
 }
 impl Line {
     pub fn from(line_str: &str) -> Self {
+        Self::with_tab_style(line_str, TabStyle::default())
+    }
+
+    pub fn with_tab_style(line_str: &str, tab_style: TabStyle) -> Self {
         debug_assert!(line_str.is_empty() || line_str.lines().count() == 1);
-        let fragments = Self::str_to_fragments(line_str);
+        let fragments = Self::str_to_fragments(line_str, tab_style);
         Self {
             fragments,
             string: String::from(line_str),
+            tab_style,
+        }
+    }
+
+    pub fn set_tab_style(&mut self, tab_style: TabStyle) {
+        if self.tab_style != tab_style {
+            self.tab_style = tab_style;
+            self.rebuild_fragments();
         }
     }
 
-    fn str_to_fragments(line_str: &str) -> Vec<TextFragment> {
+    fn str_to_fragments(line_str: &str, tab_style: TabStyle) -> Vec<TextFragment> {
+        let mut column: ColIdx = 0;
         line_str
             .grapheme_indices(true)
             .map(|(byte_idx, grapheme)| {
-                let (replacement, rendered_width) = Self::get_replacement_character(grapheme)
-                    .map_or_else(
-                        || {
-                            let unicode_width = grapheme.width();
-                            let rendered_width = match unicode_width {
-                                0 | 1 => GraphemeWidth::Half,
-                                _ => GraphemeWidth::Full,
-                            };
-                            (None, rendered_width)
+                let (replacement, rendered_width) = if grapheme == "\t" {
+                    let glyph = if tab_style.visible { '→' } else { ' ' };
+                    (Some(glyph), GraphemeWidth::Tab(tab_style.columns_at(column)))
+                } else {
+                    // Replacements are measured like any other text, since
+                    // they are what actually ends up on screen.
+                    Self::get_replacement_character(grapheme).map_or_else(
+                        || (None, Self::rendered_width(grapheme)),
+                        |replacement| {
+                            let rendered_width =
+                                Self::rendered_width(replacement.encode_utf8(&mut [0; 4]));
+                            (Some(replacement), rendered_width)
                         },
-                        |replacement| (Some(replacement), GraphemeWidth::Half),
-                    );
+                    )
+                };
+                column = column.saturating_add(rendered_width.into());
                 TextFragment {
                     grapheme: grapheme.to_string(),
                     rendered_width,
@@ -56,25 +79,28 @@ impl Line {
             })
             .collect()
     }
+    // Zero-width graphemes are replaced with `·` before they get here, so
+    // every grapheme takes at least one column.
+    fn rendered_width(string: &str) -> GraphemeWidth {
+        match string.width() {
+            0 | 1 => GraphemeWidth::Half,
+            _ => GraphemeWidth::Full,
+        }
+    }
+
     fn rebuild_fragments(&mut self) {
-        self.fragments = Self::str_to_fragments(&self.string);
+        self.fragments = Self::str_to_fragments(&self.string, self.tab_style);
     }
 
     fn get_replacement_character(for_str: &str) -> Option<char> {
         let width = for_str.width();
+        let mut chars = for_str.chars();
+        let is_single_control = chars.next().is_some_and(char::is_control) && chars.next().is_none();
         match for_str {
             " " => None,
-            "\t" => Some(' '),
+            _ if is_single_control => Some('▯'),
             _ if width > 0 && for_str.trim().is_empty() => Some('␣'),
-            _ if width == 0 => {
-                let mut chars = for_str.chars();
-                if let Some(ch) = chars.next() {
-                    if ch.is_control() && chars.next().is_none() {
-                        return Some('▯');
-                    }
-                }
-                Some('·')
-            },
+            _ if width == 0 => Some('·'),
             _ => None,
         }
     }
@@ -82,6 +108,10 @@ impl Line {
         self.get_annotated_visible_substr(range, None).to_string()
     }
 
+    /// The part of the line between the columns in `range`, with
+    /// `annotations` (byte ranges into the whole line) moved along as the
+    /// line is clipped. A grapheme cut off at either edge becomes `⋯`,
+    /// annotated as `Clip` so it can't be mistaken for a typed `⋯`.
     pub fn get_annotated_visible_substr(
         &self,
         range: Range<ColIdx>,
@@ -108,8 +138,24 @@ impl Line {
                 continue;
             }
 
+            // A grapheme cut by an edge becomes `⋯` padded to the columns
+            // it keeps, so wide ones and tabs neither overrun the row nor
+            // shift what follows.
             if fragment_start < range.end && fragment_end > range.end {
-                result.replace(fragment.start, self.string.len(), "⋯");
+                let padding = range
+                    .end
+                    .saturating_sub(fragment_start.max(range.start))
+                    .saturating_sub(1);
+                result.replace(
+                    fragment.start,
+                    self.string.len(),
+                    &format!("{CLIP_INDICATOR}{}", " ".repeat(padding)),
+                );
+                result.add_annotation(
+                    AnnotationType::Clip,
+                    fragment.start,
+                    fragment.start.saturating_add(CLIP_INDICATOR.len_utf8()),
+                );
                 continue;
             } else if fragment_start == range.end {
                 result.truncate_right_from(fragment.start);
@@ -120,20 +166,28 @@ impl Line {
                 result.truncate_left_until(fragment.start.saturating_add(fragment.grapheme.len()));
                 break;
             } else if fragment_start < range.start && fragment_end > range.start {
+                let padding = fragment_end.saturating_sub(range.start).saturating_sub(1);
                 result.replace(
                     0,
                     fragment.start.saturating_add(fragment.grapheme.len()),
-                    "⋯",
+                    &format!("{CLIP_INDICATOR}{}", " ".repeat(padding)),
                 );
+                result.add_annotation(AnnotationType::Clip, 0, CLIP_INDICATOR.len_utf8());
                 break;
             }
 
-            if fragment_start >= range.start && fragment_end <= range.end {
-                if let Some(replacement) = fragment.replacement {
-                    let start = fragment.start;
-                    let end = start.saturating_add(fragment.grapheme.len());
-                    result.replace(start, end, &replacement.to_string());
-                }
+            if fragment_start >= range.start
+                && fragment_end <= range.end
+                && let Some(replacement) = fragment.replacement
+            {
+                let start = fragment.start;
+                let end = start.saturating_add(fragment.grapheme.len());
+                let padding = usize::from(fragment.rendered_width).saturating_sub(1);
+                let replacement = match fragment.rendered_width {
+                    GraphemeWidth::Tab(_) => format!("{replacement}{}", " ".repeat(padding)),
+                    _ => replacement.to_string(),
+                };
+                result.replace(start, end, &replacement);
             }
         }
 
@@ -155,18 +209,180 @@ impl Line {
         self.width_until(self.grapheme_count())
     }
 
-    pub fn insert_char(&mut self, character: char, at: GraphemeIdx) {
-        debug_assert!(at.saturating_sub(1) <= self.grapheme_count());
-        if let Some(fragment) = self.fragments.get(at) {
-            self.string.insert(fragment.start, character);
-        } else {
-            self.string.push(character);
+    /// The first grapheme starting at or after column `width`.
+    pub fn grapheme_idx_at_width(&self, width: ColIdx) -> GraphemeIdx {
+        let mut column: ColIdx = 0;
+        for (idx, fragment) in self.fragments.iter().enumerate() {
+            if column >= width {
+                return idx;
+            }
+            column = column.saturating_add(fragment.rendered_width.into());
+        }
+        self.grapheme_count()
+    }
+
+    /// The grapheme boundary closest to column `width`, the earlier one on
+    /// a tie, or the end of the line if `width` is past it.
+    pub fn grapheme_idx_near_width(&self, width: ColIdx) -> GraphemeIdx {
+        let mut column: ColIdx = 0;
+        for (idx, fragment) in self.fragments.iter().enumerate() {
+            let next = column.saturating_add(fragment.rendered_width.into());
+            if width < next {
+                return if width.saturating_sub(column) <= next.saturating_sub(width) {
+                    idx
+                } else {
+                    idx.saturating_add(1)
+                };
+            }
+            column = next;
+        }
+        self.grapheme_count()
+    }
+
+    /// Rewrites the tabs in the indentation, or everywhere with `all`, as
+    /// the spaces they span. Returns whether anything changed.
+    pub fn tabs_to_spaces(&mut self, all: bool) -> bool {
+        let mut in_indentation = true;
+        let mut changed = false;
+        let mut string = String::with_capacity(self.string.len());
+        for fragment in &self.fragments {
+            in_indentation &= fragment.grapheme == " " || fragment.grapheme == "\t";
+            if fragment.grapheme == "\t" && (all || in_indentation) {
+                string.push_str(&" ".repeat(fragment.rendered_width.into()));
+                changed = true;
+            } else {
+                string.push_str(&fragment.grapheme);
+            }
+        }
+        if changed {
+            self.string = string;
+            self.rebuild_fragments();
         }
+        changed
+    }
+
+    /// Returns whether there was any trailing whitespace to drop.
+    pub fn trim_trailing_whitespace(&mut self) -> bool {
+        let len = self.string.trim_end().len();
+        if len == self.string.len() {
+            return false;
+        }
+        self.string.truncate(len);
+        self.rebuild_fragments();
+        true
+    }
+
+    /// Rewrites the indentation as tabs, padded with spaces to its width.
+    /// Returns whether anything changed.
+    pub fn spaces_to_tabs(&mut self) -> bool {
+        let indentation_len = self
+            .fragments
+            .iter()
+            .take_while(|fragment| fragment.grapheme == " " || fragment.grapheme == "\t")
+            .count();
+        let width = self.width_until(indentation_len);
+        let tab_width = self.tab_style.width;
+        let indentation = format!(
+            "{}{}",
+            "\t".repeat(width.checked_div(tab_width).unwrap_or(0)),
+            " ".repeat(width.checked_rem(tab_width).unwrap_or(width))
+        );
+        let byte_idx = self
+            .fragments
+            .get(indentation_len)
+            .map_or(self.string.len(), |fragment| fragment.start);
+        if self.string.get(..byte_idx) == Some(indentation.as_str()) {
+            return false;
+        }
+        self.string.replace_range(..byte_idx, &indentation);
         self.rebuild_fragments();
+        true
+    }
+
+    /// Inserts `character` before grapheme `at` and returns the grapheme
+    /// index right after it. That isn't always `at + 1`: a combining mark
+    /// or joiner merges with its neighbours into one grapheme.
+    pub fn insert_char(&mut self, character: char, at: GraphemeIdx) -> GraphemeIdx {
+        debug_assert!(at <= self.grapheme_count());
+        let byte_idx = self
+            .fragments
+            .get(at)
+            .map_or(self.string.len(), |fragment| fragment.start);
+        self.string.insert(byte_idx, character);
+        self.rebuild_fragments();
+        self.byte_idx_to_grapheme_idx(byte_idx.saturating_add(character.len_utf8()))
+            .unwrap_or_else(|| self.grapheme_count())
+    }
+
+    pub fn insert_str(&mut self, string: &str, at: GraphemeIdx) {
+        debug_assert!(at <= self.grapheme_count());
+        let byte_idx = self
+            .fragments
+            .get(at)
+            .map_or(self.string.len(), |fragment| fragment.start);
+        self.string.insert_str(byte_idx, string);
+        self.rebuild_fragments();
+    }
+
+    pub fn grapheme_at(&self, at: GraphemeIdx) -> Option<&str> {
+        self.fragments
+            .get(at)
+            .map(|fragment| fragment.grapheme.as_str())
+    }
+
+    /// Letters, digits and underscores make up words.
+    fn is_word_grapheme(grapheme: &str) -> bool {
+        grapheme
+            .chars()
+            .next()
+            .is_some_and(|character| character.is_alphanumeric() || character == '_')
+    }
+
+    /// The graphemes of the word at `at`, or of the one ending right before
+    /// it, so a caret just past a word still counts as on it.
+    pub fn word_at(&self, at: GraphemeIdx) -> Option<Range<GraphemeIdx>> {
+        let is_word = |idx: GraphemeIdx| self.grapheme_at(idx).is_some_and(Self::is_word_grapheme);
+        let at = if is_word(at) {
+            at
+        } else {
+            at.checked_sub(1).filter(|&idx| is_word(idx))?
+        };
+        let start = (0..at)
+            .rev()
+            .find(|&idx| !is_word(idx))
+            .map_or(0, |idx| idx.saturating_add(1));
+        let end = (at..self.grapheme_count())
+            .find(|&idx| !is_word(idx))
+            .unwrap_or_else(|| self.grapheme_count());
+        Some(start..end)
+    }
+
+    /// The case-sensitive matches of `word` that are not part of a longer
+    /// word.
+    pub fn find_whole_words(&self, word: &str) -> Vec<(ByteIdx, GraphemeIdx)> {
+        let word_len = word.graphemes(true).count();
+        let is_word = |idx: GraphemeIdx| self.grapheme_at(idx).is_some_and(Self::is_word_grapheme);
+        self.find_all(word, 0..self.string.len(), false)
+            .into_iter()
+            .filter(|&(_, grapheme_idx)| {
+                !grapheme_idx.checked_sub(1).is_some_and(is_word)
+                    && !is_word(grapheme_idx.saturating_add(word_len))
+            })
+            .collect()
     }
 
     pub fn append_char(&mut self, character: char) {
-        self.insert_char(character, self.grapheme_count());
+        let _ = self.insert_char(character, self.grapheme_count());
+    }
+
+    /// Replaces the graphemes in `range` with `text`, which may have a
+    /// different length.
+    pub fn replace_range(&mut self, range: Range<GraphemeIdx>, text: &str) {
+        debug_assert!(range.end <= self.grapheme_count());
+        let start = self.byte_idx_after(0, range.start);
+        let end = self.byte_idx_after(range.start, range.len());
+        self.string.replace_range(start..end, text);
+        self.rebuild_fragments();
     }
 
     pub fn delete(&mut self, at: GraphemeIdx) {
@@ -179,6 +395,21 @@ impl Line {
         }
     }
 
+    /// Swaps the grapheme at `at` with the one following it.
+    pub fn swap_graphemes(&mut self, at: GraphemeIdx) {
+        let (Some(first), Some(second)) = (
+            self.fragments.get(at),
+            self.fragments.get(at.saturating_add(1)),
+        ) else {
+            return;
+        };
+        let start = first.start;
+        let end = second.start.saturating_add(second.grapheme.len());
+        let swapped = format!("{}{}", second.grapheme, first.grapheme);
+        self.string.replace_range(start..end, &swapped);
+        self.rebuild_fragments();
+    }
+
     pub fn delete_last(&mut self) {
         self.delete(self.grapheme_count().saturating_sub(1));
     }
@@ -191,9 +422,9 @@ impl Line {
         if let Some(fragment) = self.fragments.get(at) {
             let remainder = self.string.split_off(fragment.start);
             self.rebuild_fragments();
-            Self::from(&remainder)
+            Self::with_tab_style(&remainder, self.tab_style)
         } else {
-            Self::default()
+            Self::with_tab_style("", self.tab_style)
         }
     }
 
@@ -230,13 +461,14 @@ impl Line {
         &self,
         query: &str,
         from_grapheme_idx: GraphemeIdx,
+        ignore_case: bool,
     ) -> Option<GraphemeIdx> {
         debug_assert!(from_grapheme_idx <= self.grapheme_count());
         if from_grapheme_idx == self.grapheme_count() {
             return None;
         }
         let start = self.grapheme_idx_to_byte_idx(from_grapheme_idx);
-        self.find_all(query, start..self.string.len())
+        self.find_all(query, start..self.string.len(), ignore_case)
             .first()
             .map(|(_, grapheme_idx)| *grapheme_idx)
     }
@@ -245,30 +477,46 @@ impl Line {
         &self,
         query: &str,
         from_grapheme_idx: GraphemeIdx,
+        ignore_case: bool,
     ) -> Option<GraphemeIdx> {
         debug_assert!(from_grapheme_idx <= self.grapheme_count());
         if from_grapheme_idx == 0 {
             return None;
         }
-        let end_byte_idx = if from_grapheme_idx == self.grapheme_count() {
-            self.string.len()
-        } else {
-            self.grapheme_idx_to_byte_idx(from_grapheme_idx)
-        };
-        self.find_all(query, 0..end_byte_idx)
-            .last()
-            .map(|(_, grapheme_idx)| *grapheme_idx)
+        // Matches are searched across the whole line so that a match starting
+        // before the cursor but extending past it is still found.
+        self.find_all(query, 0..self.string.len(), ignore_case)
+            .into_iter()
+            .rev()
+            .find(|&(_, grapheme_idx)| grapheme_idx < from_grapheme_idx)
+            .map(|(_, grapheme_idx)| grapheme_idx)
     }
 
-    pub fn find_all(&self, query: &str, range: Range<ByteIdx>) -> Vec<(ByteIdx, GraphemeIdx)> {
+    pub fn find_all(
+        &self,
+        query: &str,
+        range: Range<ByteIdx>,
+        ignore_case: bool,
+    ) -> Vec<(ByteIdx, GraphemeIdx)> {
+        if ignore_case {
+            return self.find_all_ignoring_case(query, range);
+        }
         let end = min(range.end, self.string.len());
         let start = range.start;
         debug_assert!(start <= end);
         self.string.get(start..end).map_or_else(
-            || Vec::new(),
+            Vec::new,
             |substr| {
+                // Overlapping matches count too, as they do when ignoring
+                // case: `match_indices` would skip the one at 1 in "aaa"
+                // for "aa", which is the nearest one before 2.
                 let potential_matches: Vec<ByteIdx> = substr
-                    .match_indices(query)
+                    .char_indices()
+                    .filter(|&(relative_start_idx, _)| {
+                        substr
+                            .get(relative_start_idx..)
+                            .is_some_and(|rest| rest.starts_with(query))
+                    })
                     .map(|(relative_start_idx, _)| relative_start_idx.saturating_add(start))
                     .collect();
                 self.match_grapheme_clusters(&potential_matches, query)
@@ -276,6 +524,43 @@ impl Line {
         )
     }
 
+    /// Compares grapheme by grapheme, so a match always covers as many
+    /// graphemes as the query even where lowercasing changes the length.
+    fn find_all_ignoring_case(
+        &self,
+        query: &str,
+        range: Range<ByteIdx>,
+    ) -> Vec<(ByteIdx, GraphemeIdx)> {
+        let query: Vec<String> = query.graphemes(true).map(str::to_lowercase).collect();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let end = min(range.end, self.string.len());
+        self.fragments
+            .windows(query.len())
+            .enumerate()
+            .filter_map(|(grapheme_idx, window)| {
+                let start = window.first()?.start;
+                let last = window.last()?;
+                let match_end = last.start.saturating_add(last.grapheme.len());
+                (start >= range.start
+                    && match_end <= end
+                    && window
+                        .iter()
+                        .zip(&query)
+                        .all(|(fragment, grapheme)| fragment.grapheme.to_lowercase() == *grapheme))
+                .then_some((start, grapheme_idx))
+            })
+            .collect()
+    }
+
+    /// The byte index just past `count` graphemes starting at `grapheme_idx`.
+    pub fn byte_idx_after(&self, grapheme_idx: GraphemeIdx, count: usize) -> ByteIdx {
+        self.fragments
+            .get(grapheme_idx.saturating_add(count))
+            .map_or(self.string.len(), |fragment| fragment.start)
+    }
+
     fn match_grapheme_clusters(
         &self,
         matches: &[ByteIdx],
@@ -315,3 +600,151 @@ impl Deref for Line {
         &self.string
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widths(line: &Line) -> Vec<usize> {
+        line.fragments
+            .iter()
+            .map(|fragment| usize::from(fragment.rendered_width))
+            .collect()
+    }
+
+    #[test]
+    fn combining_mark_stays_in_one_column() {
+        let line = Line::from("ae\u{301}b");
+        assert_eq!(line.grapheme_count(), 3);
+        assert_eq!(widths(&line), [1, 1, 1]);
+        assert_eq!(line.width_until(2), 2);
+        assert_eq!(line.width(), 3);
+        assert_eq!(line.find_all("b", 0..line.len(), false), [(4, 2)]);
+        assert_eq!(
+            line.find_all("e\u{301}", 0..line.len(), false),
+            [(1, 1)]
+        );
+        // A bare `e` is not a match inside the combined grapheme.
+        assert!(line.find_all("e", 0..line.len(), false).is_empty());
+    }
+
+    #[test]
+    fn emoji_zwj_sequence_is_one_wide_grapheme() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let line = Line::from(&format!("a{family}b"));
+        assert_eq!(line.grapheme_count(), 3);
+        assert_eq!(widths(&line), [1, 2, 1]);
+        assert_eq!(line.width_until(2), 3);
+        assert_eq!(line.width(), 4);
+        let after = family.len().saturating_add(1);
+        assert_eq!(line.find_all("b", 0..line.len(), false), [(after, 2)]);
+        assert_eq!(line.find_all(family, 0..line.len(), false), [(1, 1)]);
+        assert_eq!(line.search_backward("b", 3, false), Some(2));
+    }
+
+    #[test]
+    fn zero_width_grapheme_is_replaced() {
+        let line = Line::from("a\u{200B}b");
+        assert_eq!(line.grapheme_count(), 3);
+        assert_eq!(widths(&line), [1, 1, 1]);
+        assert_eq!(line.get_visible_graphemes(0..3), "a·b");
+    }
+
+    #[test]
+    fn overlapping_matches_are_all_found() {
+        let line = Line::from("aaaa");
+        let all = [(0, 0), (1, 1), (2, 2)];
+        assert_eq!(line.find_all("aa", 0..line.len(), false), all);
+        assert_eq!(line.find_all("aa", 0..line.len(), true), all);
+        assert_eq!(line.search_forward("aa", 1, false), Some(1));
+        assert_eq!(line.search_backward("aa", 3, false), Some(2));
+        assert_eq!(line.search_backward("aa", 2, false), Some(1));
+    }
+
+    #[test]
+    fn search_backward_stops_at_the_nearest_match() {
+        let line = Line::from("ababab");
+        assert_eq!(
+            line.find_all("ab", 0..line.len(), false),
+            [(0, 0), (2, 2), (4, 4)]
+        );
+        assert_eq!(line.search_backward("ab", 3, false), Some(2));
+        assert_eq!(line.search_backward("ab", 2, false), Some(0));
+        assert_eq!(line.search_backward("ab", 0, false), None);
+        assert_eq!(line.search_forward("ab", 3, false), Some(4));
+    }
+
+    #[test]
+    fn wide_graphemes_at_the_edges_fill_the_row_exactly() {
+        let line = Line::from("a中b文字cd\t!");
+        let line_width = line.width();
+        for width in 1..=6 {
+            for left in 0..line_width {
+                let right = left.saturating_add(width);
+                let row = line.get_annotated_visible_substr(left..right, None).to_string();
+                let expected = right.min(line_width).saturating_sub(left);
+                assert_eq!(row.width(), expected, "{left}..{right}: {row:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn insert_char_returns_the_index_after_its_grapheme() {
+        let mut line = Line::from("ab");
+        assert_eq!(line.insert_char('x', 1), 2);
+        assert_eq!(line.insert_char('!', 3), 4);
+
+        // The mark joins the `a`, so the grapheme count stays the same.
+        let mut line = Line::from("ab");
+        assert_eq!(line.insert_char('\u{301}', 1), 1);
+        assert_eq!(line.grapheme_count(), 2);
+
+        // The `e` takes the lone mark after it into its grapheme.
+        let mut line = Line::from("\u{301}b");
+        assert_eq!(line.insert_char('e', 0), 1);
+        assert_eq!(line.grapheme_count(), 2);
+
+        // The second regional indicator completes the flag.
+        let mut line = Line::from("\u{1F1E9}");
+        assert_eq!(line.insert_char('\u{1F1EA}', 1), 1);
+        assert_eq!(line.grapheme_count(), 1);
+    }
+
+    /// The visible columns of `line` in `range` with one `Match` annotation
+    /// over the bytes `start..end`, as (text, annotation) parts.
+    fn visible_parts(
+        line: &Line,
+        range: Range<ColIdx>,
+        start: ByteIdx,
+        end: ByteIdx,
+    ) -> Vec<(String, Option<AnnotationType>)> {
+        let annotations = vec![Annotation {
+            annotation_type: AnnotationType::Match,
+            start,
+            end,
+        }];
+        line.get_annotated_visible_substr(range, Some(&annotations))
+            .into_iter()
+            .map(|part| (part.string.to_string(), part.annotation_type))
+            .collect()
+    }
+
+    #[test]
+    fn annotations_follow_the_horizontal_scroll() {
+        let line = Line::from("0123456789abcdef");
+        let plain = |text: &str| (text.to_string(), None);
+        let found = |text: &str| (text.to_string(), Some(AnnotationType::Match));
+
+        // Entirely past `left`.
+        assert_eq!(
+            visible_parts(&line, 5..12, 8, 10),
+            [plain("567"), found("89"), plain("ab")]
+        );
+        // Cut by the left edge.
+        assert_eq!(visible_parts(&line, 5..10, 3, 7), [found("56"), plain("789")]);
+        // Cut by the right edge.
+        assert_eq!(visible_parts(&line, 2..10, 8, 12), [plain("234567"), found("89")]);
+        // Past the right edge, so not visible at all.
+        assert_eq!(visible_parts(&line, 0..4, 8, 10), [plain("0123")]);
+    }
+}
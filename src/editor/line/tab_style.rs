@@ -0,0 +1,26 @@
+const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// How tabs are laid out: each one spans to the next multiple of `width`,
+/// drawn as `→` and padding when `visible`, else as blanks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TabStyle {
+    pub width: usize,
+    pub visible: bool,
+}
+
+impl Default for TabStyle {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_TAB_WIDTH,
+            visible: false,
+        }
+    }
+}
+
+impl TabStyle {
+    /// The number of columns a tab starting at `column` occupies.
+    pub fn columns_at(self, column: usize) -> usize {
+        let width = self.width.max(1);
+        width.saturating_sub(column.checked_rem(width).unwrap_or(0))
+    }
+}
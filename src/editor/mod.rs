@@ -1,19 +1,29 @@
 use crate::prelude::*;
 use std::{
-    env,
-    io::Error,
+    fs,
+    io::{Error, Read, stdin, stdout},
     panic::{set_hook, take_hook},
+    path::Path,
+    time::{Duration, Instant},
 };
 
-use crossterm::event::{Event, KeyEvent, KeyEventKind, read};
+use crossterm::event::{Event, KeyEvent, KeyEventKind, poll, read};
 
+mod alternate_file;
 mod annotated_string;
+mod arguments;
+mod check;
 mod annotation;
 mod annotation_type;
 mod command;
+mod config;
 mod document_status;
+mod editorconfig;
+mod file_summary;
 mod file_type;
 mod line;
+mod path_completion;
+mod recent_files;
 mod terminal;
 mod ui_components;
 
@@ -23,23 +33,55 @@ use self::{
     annotation_type::AnnotationType,
     command::{
         Command::{self, Edit, Move, System},
-        Edit::InsertNewline,
+        Edit::{Insert, InsertNewline},
         Move::{Down, Left, Right, Up},
-        System::{Dismiss, Quit, Resize, Save, Search},
+        System::{
+            AlternateFile, CommandPalette, Count, DiffWithDisk, Dismiss, ExportHtml, FileInfo, GoTo,
+            Help, Interrupt, JumpBack, JumpForward, JumpToMark, NextAnnotation, NextFile, Open,
+            OpenRecent, PrevAnnotation, QuotedInsert, Quit, RepeatEdit, Resize, Save, SaveAndQuit,
+            Search, SetMark, SpacesToTabs, SqueezeBlankLines, TabsToSpaces, ToggleChangeGutter,
+        },
+        help_text, literal_char, named_commands,
     },
+    config::{ConfigFile, EditorConfig},
     document_status::DocumentStatus,
+    file_summary::FileSummary,
     file_type::FileType,
-    line::Line,
-    terminal::Terminal,
-    ui_components::{CommandBar, MessageBar, StatusBar, UIComponent, View},
+    line::{Line, TabStyle},
+    path_completion::complete_path,
+    recent_files::RecentFiles,
+    terminal::{Renderer, Terminal},
+    ui_components::{
+        CommandBar, CommandList, LineEnding, MessageBar, SearchCase, SearchScroll, StatusBar,
+        UIComponent, View,
+    },
 };
 
+pub use self::{arguments::Arguments, check::check_file};
+
 const QUIT_TIMES: u8 = 3;
+const SIZE_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_PALETTE_ROWS: usize = 8;
+const MAX_COUNT: usize = 9999;
+/// Up or Down repeated within this long of the previous one accelerates.
+const ACCELERATION_WINDOW: Duration = Duration::from_millis(150);
+const MAX_ACCELERATED_STEP: usize = 8;
+/// Loading, diffing or exporting more than this many bytes shows a busy
+/// message first, so the editor doesn't look frozen.
+const BUSY_THRESHOLD: u64 = 1 << 20;
 
 #[derive(Eq, PartialEq, Default)]
 enum PromptType {
     Search,
     Save,
+    SetMark,
+    JumpToMark,
+    ConfirmOpen,
+    Open,
+    OpenRecent,
+    CommandPalette,
+    ExportHtml,
+    GoTo,
     #[default]
     None,
 }
@@ -50,50 +92,209 @@ impl PromptType {
     }
 }
 
-#[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Editor {
     should_quit: bool,
+    write_stdout: bool,
+    lossy: bool,
+    renderer: Box<dyn Renderer>,
     view: View,
     status_bar: StatusBar,
     message_bar: MessageBar,
     command_bar: CommandBar,
+    command_list: CommandList,
+    palette_matches: Vec<Command>,
     prompt_type: PromptType,
     terminal_size: Size,
     title: String,
     quit_times: u8,
+    /// Set by `SaveAndQuit` until the save it started succeeds or fails.
+    quit_after_save: bool,
+    recent_files: RecentFiles,
+    count: Option<usize>,
+    last_edit: Option<command::Edit>,
+    quoted_insert: bool,
+    /// The last move and when it arrived, for `move_acceleration`.
+    last_move: Option<(command::Move, Instant)>,
+    move_streak: usize,
+    /// The file arguments, cycled through by `NextFile`.
+    file_arguments: Vec<String>,
+    file_argument_idx: usize,
+    /// Patterns relating a file to its alternate, for `AlternateFile`.
+    alternate_files: Vec<(String, String)>,
+    /// Opened once the discard prompt is answered, instead of asking for a
+    /// name.
+    pending_open: Option<String>,
 }
 impl Editor {
-    pub fn new() -> Result<Self, Error> {
+    pub fn new(arguments: &Arguments) -> Result<Self, Error> {
         let current_hook = take_hook();
         set_hook(Box::new(move |panic_info| {
             let _ = Terminal::terminate();
             current_hook(panic_info);
         }));
+        if arguments.write_stdout {
+            Terminal::draw_on_tty()?;
+        }
+        Self::with_renderer(arguments, Box::new(Terminal))
+    }
+
+    /// Like `new`, but draws on `renderer` instead of the terminal and
+    /// leaves the panic hook alone.
+    pub fn with_renderer(
+        arguments: &Arguments,
+        renderer: Box<dyn Renderer>,
+    ) -> Result<Self, Error> {
+        // Piped input has to be consumed before the terminal switches to raw
+        // mode; crossterm then reads keys from the controlling terminal.
+        let piped_text = if arguments.read_stdin {
+            let mut text = String::new();
+            stdin().read_to_string(&mut text)?;
+            Some(text)
+        } else {
+            None
+        };
 
-        Terminal::initialize()?;
-        let mut editor = Self::default();
-        let size = Terminal::size().unwrap_or_default();
+        let mut editor = Self {
+            should_quit: false,
+            write_stdout: false,
+            lossy: false,
+            renderer,
+            view: View::default(),
+            status_bar: StatusBar::default(),
+            message_bar: MessageBar::default(),
+            command_bar: CommandBar::default(),
+            command_list: CommandList::default(),
+            palette_matches: Vec::new(),
+            prompt_type: PromptType::default(),
+            terminal_size: Size::default(),
+            title: String::new(),
+            quit_times: 0,
+            quit_after_save: false,
+            recent_files: RecentFiles::default(),
+            count: None,
+            last_edit: None,
+            quoted_insert: false,
+            last_move: None,
+            move_streak: 0,
+            file_arguments: Vec::new(),
+            file_argument_idx: 0,
+            alternate_files: Vec::new(),
+            pending_open: None,
+        };
+        editor.renderer.initialize()?;
+        editor.write_stdout = arguments.write_stdout;
+        editor.lossy = arguments.lossy;
+        editor.recent_files = RecentFiles::load();
+        let (mut config_file, config_warnings) = ConfigFile::load();
+        if arguments.search_scroll == SearchScroll::Minimal {
+            config_file.set_override("search_scroll", "minimal");
+        }
+        if let Some(format) = config_file.status_format() {
+            editor.status_bar.set_format(format);
+        }
+        if let Some(duration) = config_file.message_duration() {
+            editor.message_bar.set_duration(duration);
+        }
+        editor.alternate_files = config_file
+            .alternate_files()
+            .map_or_else(alternate_file::default_rules, <[_]>::to_vec);
+        editor.view.set_config_file(config_file);
+        let size = editor.renderer.size().unwrap_or_default();
         editor.handle_resize_command(size);
-        editor.update_message("HELP: Ctrl-F = find | Ctrl-S = save | Ctrl-Q = quit");
+        editor.update_message(
+            "HELP: Ctrl-F = find | Ctrl-S = save | F1 = help | Ctrl-Q = quit (not Ctrl-C)",
+        );
+        editor.report_config_warnings(&config_warnings);
 
-        let args: Vec<String> = env::args().collect();
-        if let Some(file_name) = args.get(1) {
-            debug_assert!(!file_name.is_empty());
-            if editor.view.load(file_name).is_err() {
-                editor.update_message(&format!("ERR:Could not open file: {file_name}"));
+        if let Some(text) = piped_text {
+            editor.view.set_text(&text);
+            editor.report_whitespace_issues();
+        } else if !arguments.file_names.is_empty() {
+            editor.file_arguments.clone_from(&arguments.file_names);
+            let mut failed = Vec::new();
+            for (idx, argument) in arguments.file_names.iter().enumerate() {
+                debug_assert!(!argument.is_empty());
+                match editor.open_argument(argument) {
+                    Ok(()) => {
+                        editor.file_argument_idx = idx;
+                        editor.report_whitespace_issues();
+                        break;
+                    },
+                    Err(err) => {
+                        let (file_name, _) = Self::parse_file_argument(argument);
+                        if arguments.strict {
+                            return Err(Error::new(
+                                err.kind(),
+                                format!("Could not open file: {file_name}: {err}"),
+                            ));
+                        }
+                        failed.push(file_name);
+                    },
+                }
+            }
+            if !failed.is_empty() {
+                editor.update_error(&format!("Could not open file: {}", failed.join(", ")));
             }
         }
 
+        editor.view.set_recent_files(editor.recent_files.paths());
         editor.refresh_status();
         Ok(editor)
     }
 
+    /// Splits a trailing `:line` or `:line:col` (both 1-based) off a file
+    /// argument. Paths that exist as given are never split.
+    fn parse_file_argument(argument: &str) -> (&str, Option<Location>) {
+        if Path::new(argument).exists() {
+            return (argument, None);
+        }
+        let Some((rest, last)) = Self::split_trailing_number(argument) else {
+            return (argument, None);
+        };
+        let (file_name, line, col) = match Self::split_trailing_number(rest) {
+            Some((file_name, line)) => (file_name, line, Some(last)),
+            None => (rest, last, None),
+        };
+        let location = Location {
+            grapheme_idx: col.map_or(0, |col| col.saturating_sub(1)),
+            line_idx: line.saturating_sub(1),
+        };
+        (file_name, Some(location))
+    }
+
+    fn split_trailing_number(value: &str) -> Option<(&str, usize)> {
+        let (rest, number) = value.rsplit_once(':')?;
+        if rest.is_empty() || number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        number.parse().ok().map(|number| (rest, number))
+    }
+
     pub fn run(&mut self) {
         loop {
             self.refresh_screen();
             if self.should_quit {
                 break;
             }
+            match poll(SIZE_CHECK_INTERVAL) {
+                Ok(true) => {},
+                Ok(false) => {
+                    self.check_terminal_size();
+                    continue;
+                },
+                Err(err) => {
+                    #[cfg(debug_assertions)]
+                    {
+                        panic!("Could not poll for events: {err:?}");
+                    }
+                    #[cfg(not(debug_assertions))]
+                    {
+                        let _ = err;
+                        continue;
+                    }
+                },
+            }
             match read() {
                 Ok(event) => self.evaluate_event(event),
                 Err(err) => {
@@ -118,27 +319,37 @@ impl Editor {
         }
 
         let bottom_bar_row = height.saturating_sub(1);
-        let _ = Terminal::hide_caret();
+        let _ = self.renderer.hide_caret();
 
         if self.in_prompt() {
-            self.command_bar.render(bottom_bar_row);
+            self.command_bar.render(self.renderer.as_mut(), bottom_bar_row);
         } else {
-            self.message_bar.render(bottom_bar_row);
+            self.message_bar.render(self.renderer.as_mut(), bottom_bar_row);
         }
 
         if height > 1 {
-            self.status_bar.render(height.saturating_sub(2));
+            self.status_bar.render(self.renderer.as_mut(), height.saturating_sub(2));
         }
 
         if height > 2 {
-            self.view.render(0);
+            let palette_open = self.prompt_type == PromptType::CommandPalette;
+            if palette_open && self.view.needs_redraw() {
+                self.command_list.set_needs_redraw(true);
+            }
+            self.view.render(self.renderer.as_mut(), 0);
+            if palette_open {
+                let list_height = self
+                    .command_list
+                    .height_within(MAX_PALETTE_ROWS.min(height.saturating_sub(2)));
+                self.command_list.render(
+                    self.renderer.as_mut(),
+                    height.saturating_sub(2).saturating_sub(list_height),
+                );
+            }
         }
 
         let new_caret_pos = if self.in_prompt() {
-            Position {
-                col: self.command_bar.caret_position_col(),
-                row: bottom_bar_row,
-            }
+            Position::new(bottom_bar_row, self.command_bar.caret_position_col())
         } else {
             self.view.caret_position()
         };
@@ -146,29 +357,80 @@ impl Editor {
         debug_assert!(new_caret_pos.col <= self.terminal_size.width);
         debug_assert!(new_caret_pos.row <= self.terminal_size.height);
 
-        let _ = Terminal::move_caret_to(new_caret_pos);
-        let _ = Terminal::show_caret();
-        let _ = Terminal::execute();
+        let _ = self.renderer.move_caret_to(new_caret_pos);
+        let _ = self.renderer.show_caret();
+        let _ = self.renderer.execute();
     }
 
     pub fn refresh_status(&mut self) {
         let status = self.view.get_status();
         let title = format!("{} - {NAME}", status.file_name);
         self.status_bar.update_status(status);
-        if title != self.title && matches!(Terminal::set_title(&title), Ok(())) {
+        if title != self.title && matches!(self.renderer.set_title(&title), Ok(())) {
             self.title = title;
         }
     }
     fn evaluate_event(&mut self, event: Event) {
+        if let Event::Paste(text) = event {
+            self.handle_paste(&text);
+            return;
+        }
         let should_process = match &event {
             Event::Key(KeyEvent { kind, .. }) => kind == &KeyEventKind::Press,
             Event::Resize(_, _) => true,
             _ => false,
         };
-        if should_process {
-            if let Ok(command) = Command::try_from(event) {
-                self.process_command(command);
-            }
+        if should_process && matches!(event, Event::Key(_)) {
+            self.message_bar.dismiss_sticky();
+        }
+        if should_process
+            && self.quoted_insert
+            && let Event::Key(key_event) = event
+        {
+            self.insert_literal(key_event);
+            return;
+        }
+        if should_process && let Ok(command) = Command::try_from(event) {
+            self.process_command(command);
+        }
+    }
+
+    /// A bracketed paste arrives in one piece, so none of its characters
+    /// are taken for keys.
+    fn handle_paste(&mut self, text: &str) {
+        self.quoted_insert = false;
+        self.message_bar.dismiss_sticky();
+        match self.prompt_type {
+            PromptType::None if !self.view.is_showing_help() => self.view.paste(text),
+            PromptType::Save
+            | PromptType::Search
+            | PromptType::Open
+            | PromptType::CommandPalette
+            | PromptType::ExportHtml
+            | PromptType::GoTo => {
+                // Prompts hold one line.
+                let pasted: String = text
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .chars()
+                    .filter(|character| !character.is_control())
+                    .collect();
+                let value = self.command_bar.value();
+                self.command_bar.set_value(&format!("{value}{pasted}"));
+                self.on_prompt_change();
+            },
+            _ => {},
+        }
+    }
+
+    fn insert_literal(&mut self, key_event: KeyEvent) {
+        self.quoted_insert = false;
+        if let Some(character) = literal_char(key_event) {
+            self.update_message("");
+            self.view.insert_literal(character);
+        } else {
+            self.update_message("That key has no character to insert.");
         }
     }
 
@@ -178,14 +440,40 @@ impl Editor {
             return;
         }
 
+        // Ctrl-C cancels prompts like Esc does; it never quits the editor.
+        let command = match command {
+            System(Interrupt) if self.in_prompt() => System(Dismiss),
+            _ => command,
+        };
+
         match self.prompt_type {
             PromptType::Save => self.process_command_during_save(command),
             PromptType::Search => self.process_command_during_search(command),
+            PromptType::SetMark | PromptType::JumpToMark => {
+                self.process_command_during_mark(command);
+            },
+            PromptType::ConfirmOpen => self.process_command_during_confirm_open(command),
+            PromptType::Open => self.process_command_during_open(command),
+            PromptType::OpenRecent => self.process_command_during_open_recent(command),
+            PromptType::CommandPalette => self.process_command_during_palette(command),
+            PromptType::ExportHtml => self.process_command_during_export(command),
+            PromptType::GoTo => self.process_command_during_go_to(command),
             PromptType::None => self.process_command_no_prompt(command),
         }
     }
 
     fn process_command_no_prompt(&mut self, command: Command) {
+        if self.view.is_showing_help() {
+            self.process_command_during_help(command);
+            return;
+        }
+
+        if let System(Count(digit)) = command {
+            self.push_count_digit(digit);
+            return;
+        }
+        let count = self.take_count();
+
         if matches!(command, System(Quit)) {
             self.handle_quit_command();
             return;
@@ -196,11 +484,218 @@ impl Editor {
         match command {
             System(Search) => self.set_prompt(PromptType::Search),
             System(Save) => self.handle_save_command(),
-            Edit(edit_command) => self.view.handle_edit_command(edit_command),
-            Move(move_command) => self.view.handle_move_command(move_command),
+            System(SaveAndQuit) => self.handle_save_and_quit_command(),
+            System(ExportHtml) => self.handle_export_command(),
+            System(QuotedInsert) => {
+                self.quoted_insert = true;
+                self.update_message("Quoted insert: the next key is inserted literally.");
+            },
+            System(SqueezeBlankLines) => self.squeeze_blank_lines(),
+            System(TabsToSpaces) => self.retab(false),
+            System(SpacesToTabs) => self.retab(true),
+            System(DiffWithDisk) => self.toggle_disk_diff(),
+            System(ToggleChangeGutter) => self.toggle_change_gutter(),
+            System(SetMark) => self.set_prompt(PromptType::SetMark),
+            System(JumpToMark) => self.set_prompt(PromptType::JumpToMark),
+            System(GoTo) => self.set_prompt(PromptType::GoTo),
+            System(Open) => self.handle_open_command(),
+            System(NextFile) => self.handle_next_file_command(),
+            System(AlternateFile) => self.handle_alternate_file_command(),
+            System(OpenRecent) => {
+                if self.recent_files.paths().is_empty() {
+                    self.update_message("No recent files.");
+                } else {
+                    self.set_prompt(PromptType::OpenRecent);
+                }
+            },
+            System(JumpBack) => self.jump_back(),
+            System(JumpForward) => self.jump_forward(),
+            System(CommandPalette) => self.set_prompt(PromptType::CommandPalette),
+            System(Help) => self.view.show_help(&help_text()),
+            System(NextAnnotation) => {
+                let result = self.view.next_annotation(AnnotationType::TrailingWhitespace);
+                self.report_annotation_jump(result);
+            },
+            System(PrevAnnotation) => {
+                let result = self.view.prev_annotation(AnnotationType::TrailingWhitespace);
+                self.report_annotation_jump(result);
+            },
+            System(FileInfo) => {
+                let message = self
+                    .view
+                    .get_file_summary()
+                    .to_message(self.terminal_size.width);
+                self.update_message(&message);
+            },
+            System(Interrupt) => self.update_message("Nothing to copy. Use Ctrl-Q to quit."),
+            #[cfg(unix)]
+            System(command::System::Suspend) => self.suspend(),
+            System(RepeatEdit) => match self.last_edit {
+                Some(edit_command) => self.repeat_edit_command(edit_command, count),
+                None => self.update_message("No edit to repeat."),
+            },
+            Edit(Insert(character)) if self.view.rejects_char(character) => {
+                self.update_message("Control character ignored (Ctrl-V inserts one literally).");
+            },
+            Edit(edit_command) => {
+                self.repeat_edit_command(edit_command, count);
+                self.last_edit = Some(edit_command);
+            },
+            Move(move_command) => {
+                let step = self.move_step(move_command);
+                for _ in 0..count.saturating_mul(step) {
+                    self.view.handle_move_command(move_command);
+                }
+            },
             System(_) => {},
         }
     }
+
+    /// How many lines `move_command` moves: one, unless `move_acceleration`
+    /// is on and Up or Down keeps repeating quickly, which moves one line
+    /// more each time, up to `MAX_ACCELERATED_STEP`.
+    fn move_step(&mut self, move_command: command::Move) -> usize {
+        let now = Instant::now();
+        let repeated = self.last_move.is_some_and(|(last_move, time)| {
+            last_move == move_command && now.duration_since(time) <= ACCELERATION_WINDOW
+        });
+        self.move_streak = if repeated {
+            self.move_streak.saturating_add(1).min(MAX_ACCELERATED_STEP)
+        } else {
+            1
+        };
+        self.last_move = Some((move_command, now));
+        if self.view.config().move_acceleration && matches!(move_command, Up | Down) {
+            self.move_streak
+        } else {
+            1
+        }
+    }
+
+    fn jump_back(&mut self) {
+        if !self.view.jump_back() {
+            self.update_message("No earlier jump to go back to.");
+        }
+    }
+
+    fn jump_forward(&mut self) {
+        if !self.view.jump_forward() {
+            self.update_message("No later jump to go forward to.");
+        }
+    }
+
+    fn squeeze_blank_lines(&mut self) {
+        let message = match self.view.squeeze_blank_lines() {
+            None => "The buffer is read-only.".to_string(),
+            Some(0) => "No blank lines to squeeze.".to_string(),
+            Some(1) => "Removed 1 blank line.".to_string(),
+            Some(removed) => format!("Removed {removed} blank lines."),
+        };
+        self.update_message(&message);
+    }
+
+    fn retab(&mut self, to_tabs: bool) {
+        let message = match self.view.retab(to_tabs) {
+            None => "The buffer is read-only.".to_string(),
+            Some(0) => "Nothing to convert.".to_string(),
+            Some(1) => "Converted 1 line.".to_string(),
+            Some(changed) => format!("Converted {changed} lines."),
+        };
+        self.update_message(&message);
+    }
+
+    fn toggle_disk_diff(&mut self) {
+        self.show_busy_for_buffer("Comparing with the file on disk…");
+        let message = match self.view.toggle_disk_diff() {
+            Ok(None) => "Diff hidden.".to_string(),
+            Ok(Some((0, 0, 0))) => "No differences to the file on disk.".to_string(),
+            Ok(Some((changed, added, removed))) => format!(
+                "Diff: {changed} changed, {added} added, {removed} removed lines. \
+                 Edit or run again to hide."
+            ),
+            Err(err) => {
+                self.update_error(&format!("Could not read the file on disk: {err}"));
+                return;
+            },
+        };
+        self.update_message(&message);
+    }
+
+    fn toggle_change_gutter(&mut self) {
+        if self.view.toggle_change_gutter() {
+            self.update_message("Change gutter shown.");
+        } else {
+            self.update_message("Change gutter hidden.");
+        }
+    }
+
+    fn push_count_digit(&mut self, digit: u32) {
+        let digit = usize::try_from(digit).unwrap_or_default();
+        let count = self
+            .count
+            .unwrap_or(0)
+            .saturating_mul(10)
+            .saturating_add(digit)
+            .min(MAX_COUNT);
+        self.count = Some(count);
+        self.update_message(&format!("Count: {count}"));
+    }
+
+    /// The pending repeat count, or 1 without one. A count of 0 still runs
+    /// the command once.
+    fn take_count(&mut self) -> usize {
+        match self.count.take() {
+            Some(count) => {
+                self.update_message("");
+                count.max(1)
+            },
+            None => 1,
+        }
+    }
+
+    fn repeat_edit_command(&mut self, edit_command: command::Edit, count: usize) {
+        for _ in 0..count {
+            self.view.handle_edit_command(edit_command);
+        }
+    }
+    // The help screen only supports moving around and searching; anything
+    // else would act on the hidden buffer, so it has to be closed first.
+    fn process_command_during_help(&mut self, command: Command) {
+        match command {
+            System(Dismiss | Help) => self.view.close_help(),
+            System(Search) => self.set_prompt(PromptType::Search),
+            Move(move_command) => self.view.handle_move_command(move_command),
+            _ => self.update_message("Press Esc to close the help screen."),
+        }
+    }
+
+    /// Hands the terminal back to the shell and stops the process until it
+    /// receives SIGCONT, then restores the editor screen.
+    #[cfg(unix)]
+    fn suspend(&mut self) {
+        let _ = self.renderer.terminate();
+        // SAFETY: raise has no memory-safety preconditions; SIGTSTP only
+        // stops the process until the shell resumes it.
+        unsafe {
+            libc::raise(libc::SIGTSTP);
+        }
+        if let Err(err) = self.renderer.initialize() {
+            self.update_error(&format!("Could not restore terminal: {err}"));
+        }
+        self.title.clear();
+        let size = self.renderer.size().unwrap_or(self.terminal_size);
+        self.handle_resize_command(size);
+    }
+
+    /// Picks up size changes whose resize event never arrived.
+    fn check_terminal_size(&mut self) {
+        if let Ok(size) = self.renderer.size()
+            && size != self.terminal_size
+        {
+            self.handle_resize_command(size);
+        }
+    }
+
     fn handle_resize_command(&mut self, size: Size) {
         self.terminal_size = size;
 
@@ -216,6 +711,15 @@ impl Editor {
         self.message_bar.resize(bar_size);
         self.command_bar.resize(bar_size);
         self.status_bar.resize(bar_size);
+        self.resize_command_list();
+    }
+
+    fn resize_command_list(&mut self) {
+        let max_height = MAX_PALETTE_ROWS.min(self.terminal_size.height.saturating_sub(2));
+        self.command_list.resize(Size {
+            height: self.command_list.height_within(max_height),
+            width: self.terminal_size.width,
+        });
     }
 
     #[allow(clippy::arithmetic_side_effects)]
@@ -238,8 +742,19 @@ impl Editor {
         }
     }
 
+    fn handle_save_and_quit_command(&mut self) {
+        if self.view.get_status().is_modified {
+            self.quit_after_save = true;
+            self.handle_save_command();
+        } else {
+            self.should_quit = true;
+        }
+    }
+
     fn handle_save_command(&mut self) {
-        if self.view.is_file_loaded() {
+        if self.view.is_lossy() {
+            self.set_prompt(PromptType::Save);
+        } else if self.view.is_file_loaded() {
             self.save(None);
         } else {
             self.set_prompt(PromptType::Save);
@@ -249,6 +764,7 @@ impl Editor {
         match command {
             System(Dismiss) => {
                 self.set_prompt(PromptType::None);
+                self.quit_after_save = false;
                 self.update_message("Save aborted.");
             },
             Edit(InsertNewline) => {
@@ -256,7 +772,8 @@ impl Editor {
                 self.save(Some(&file_name));
                 self.set_prompt(PromptType::None);
             },
-            Edit(edit_command) => self.command_bar.handle_edit_command(edit_command),
+            Edit(Insert('\t')) => self.complete_file_name(),
+            Edit(edit_command) => self.edit_prompt(edit_command),
             _ => {},
         }
     }
@@ -269,9 +786,90 @@ impl Editor {
         };
 
         if result.is_ok() {
+            if let Some(name) = file_name {
+                self.remember_recent_file(name);
+            }
             self.update_message("File saved successfully.");
+            self.should_quit = self.quit_after_save;
+        } else if self.view.is_lossy() {
+            self.update_error("A lossy buffer can only be saved to a new file.");
         } else {
-            self.update_message("Error writing file!");
+            self.update_error("Error writing file!");
+        }
+        self.quit_after_save = false;
+    }
+
+    fn process_command_during_go_to(&mut self, command: Command) {
+        match command {
+            System(Dismiss) => self.set_prompt(PromptType::None),
+            Edit(InsertNewline) => {
+                let value = self.command_bar.value();
+                self.set_prompt(PromptType::None);
+                match Self::parse_go_to(&value) {
+                    Some((line, column)) => self.view.go_to_column(
+                        line.saturating_sub(1),
+                        column.map_or(0, |column| column.saturating_sub(1)),
+                    ),
+                    None if value.is_empty() => {},
+                    None => self.update_message(&format!("Not a line or line:column: {value}")),
+                }
+            },
+            Edit(edit_command) => self.edit_prompt(edit_command),
+            _ => {},
+        }
+    }
+
+    /// Parses `line` or `line:column`, both 1-based. The column counts
+    /// display columns, so wide characters and tabs count as they look.
+    fn parse_go_to(value: &str) -> Option<(usize, Option<usize>)> {
+        let parse = |number: &str| number.trim().parse().ok().filter(|&number| number > 0);
+        match value.split_once(':') {
+            Some((line, column)) => Some((parse(line)?, Some(parse(column)?))),
+            None => Some((parse(value)?, None)),
+        }
+    }
+
+    fn handle_export_command(&mut self) {
+        let suggestion = self
+            .view
+            .get_file_path()
+            .map(|path| format!("{}.html", path.display()));
+        self.set_prompt(PromptType::ExportHtml);
+        if let Some(suggestion) = suggestion {
+            self.command_bar.set_value(&suggestion);
+        }
+    }
+
+    fn process_command_during_export(&mut self, command: Command) {
+        match command {
+            System(Dismiss) => {
+                self.set_prompt(PromptType::None);
+                self.update_message("Export aborted.");
+            },
+            Edit(InsertNewline) => {
+                let file_name = self.command_bar.value();
+                self.set_prompt(PromptType::None);
+                self.export_html(&file_name);
+            },
+            Edit(Insert('\t')) => self.complete_file_name(),
+            Edit(edit_command) => self.edit_prompt(edit_command),
+            _ => {},
+        }
+    }
+
+    fn export_html(&mut self, file_name: &str) {
+        if file_name.is_empty() {
+            self.update_message("Export aborted.");
+        } else if self.view.get_file_path() == Some(Path::new(file_name)) {
+            self.update_error("Exporting would overwrite the file being edited.");
+        } else {
+            self.show_busy_for_buffer(&format!("Exporting {file_name}…"));
+            match self.view.export_html(file_name) {
+                Ok(()) => self.update_message(&format!("Exported {file_name}.")),
+                Err(err) => {
+                    self.update_error(&format!("Could not export {file_name}: {err}"));
+                },
+            }
         }
     }
 
@@ -285,11 +883,7 @@ impl Editor {
                 self.set_prompt(PromptType::None);
                 self.view.exit_search();
             },
-            Edit(edit_command) => {
-                self.command_bar.handle_edit_command(edit_command);
-                let query = self.command_bar.value();
-                self.view.search(&query);
-            },
+            Edit(edit_command) => self.edit_prompt(edit_command),
             Move(Right | Down) => {
                 self.view.search_next();
             },
@@ -298,11 +892,348 @@ impl Editor {
             },
             _ => {},
         }
+        if self.prompt_type == PromptType::Search {
+            self.command_bar
+                .set_suffix(&self.view.search_match_summary());
+        }
+    }
+    fn handle_open_command(&mut self) {
+        if self.view.get_status().is_modified {
+            self.set_prompt(PromptType::ConfirmOpen);
+        } else {
+            self.set_prompt(PromptType::Open);
+        }
     }
+
+    fn process_command_during_confirm_open(&mut self, command: Command) {
+        match command {
+            Edit(Insert('y' | 'Y')) => match self.pending_open.take() {
+                Some(file_name) => {
+                    self.set_prompt(PromptType::None);
+                    self.open(&file_name);
+                },
+                None => self.set_prompt(PromptType::Open),
+            },
+            System(Dismiss) | Edit(Insert(_) | InsertNewline) => {
+                self.pending_open = None;
+                self.set_prompt(PromptType::None);
+                self.update_message("Open aborted.");
+            },
+            _ => {},
+        }
+    }
+
+    fn process_command_during_open(&mut self, command: Command) {
+        match command {
+            System(Dismiss) => {
+                self.set_prompt(PromptType::None);
+                self.update_message("Open aborted.");
+            },
+            Edit(InsertNewline) => {
+                let file_name = self.command_bar.value();
+                self.set_prompt(PromptType::None);
+                self.open(&file_name);
+            },
+            Edit(Insert('\t')) => self.complete_file_name(),
+            Edit(edit_command) => self.edit_prompt(edit_command),
+            _ => {},
+        }
+    }
+
+    fn complete_file_name(&mut self) {
+        if let Some(completed) = complete_path(&self.command_bar.value()) {
+            self.command_bar.set_value(&completed);
+            self.on_prompt_change();
+        }
+    }
+
+    /// Applies `edit_command` to the prompt value, letting the prompt react
+    /// if the value changed.
+    fn edit_prompt(&mut self, edit_command: command::Edit) {
+        let before = self.command_bar.value();
+        self.command_bar.handle_edit_command(edit_command);
+        if self.command_bar.value() != before {
+            self.on_prompt_change();
+        }
+    }
+
+    /// Gives live feedback while the prompt value is typed.
+    fn on_prompt_change(&mut self) {
+        let value = self.command_bar.value();
+        match self.prompt_type {
+            PromptType::Search => {
+                self.view.search(&value);
+                let not_found = !value.is_empty() && !self.view.is_search_found();
+                self.command_bar
+                    .set_value_annotation(not_found.then_some(AnnotationType::Error));
+            },
+            PromptType::Open => {
+                let (annotation, suffix) = match fs::metadata(&value) {
+                    Ok(metadata) if metadata.is_dir() => {
+                        (Some(AnnotationType::Error), "(directory)")
+                    },
+                    Err(_) if !value.is_empty() => (Some(AnnotationType::Error), "(not found)"),
+                    _ => (None, ""),
+                };
+                self.command_bar.set_value_annotation(annotation);
+                self.command_bar.set_suffix(suffix);
+            },
+            PromptType::CommandPalette => self.filter_palette(),
+            PromptType::GoTo => {
+                let invalid = !value.is_empty() && Self::parse_go_to(&value).is_none();
+                self.command_bar
+                    .set_value_annotation(invalid.then_some(AnnotationType::Error));
+            },
+            _ => {},
+        }
+    }
+
+    fn open(&mut self, file_name: &str) {
+        if file_name.is_empty() {
+            self.update_message("Open aborted.");
+            return;
+        }
+        match self.load(file_name) {
+            Ok(()) => {
+                self.remember_recent_file(file_name);
+                self.update_message(&format!("Opened {file_name}."));
+                self.report_whitespace_issues();
+            },
+            Err(err) => {
+                self.update_error(&format!("Could not open {file_name}: {err}"));
+            },
+        }
+    }
+
+    fn load(&mut self, file_name: &str) -> Result<(), Error> {
+        if fs::metadata(file_name).is_ok_and(|metadata| metadata.len() > BUSY_THRESHOLD) {
+            self.show_busy(&format!("Loading {file_name}…"));
+        }
+        if self.lossy {
+            self.view.load_lossy(file_name)?;
+            if self.view.is_lossy() {
+                self.update_warning("Invalid UTF-8 replaced; the buffer is read-only.");
+            }
+            Ok(())
+        } else {
+            self.view.load(file_name)
+        }
+    }
+
+    /// Loads a file argument, going to its `:line:col` suffix if it has one.
+    fn open_argument(&mut self, argument: &str) -> Result<(), Error> {
+        let (file_name, location) = Self::parse_file_argument(argument);
+        self.load(file_name)?;
+        self.remember_recent_file(file_name);
+        if let Some(location) = location {
+            self.view.go_to(location);
+        }
+        Ok(())
+    }
+
+    /// Opens the counterpart of the current file under `alternate_files`,
+    /// asking first if there are unsaved changes.
+    fn process_command_during_open_recent(&mut self, command: Command) {
+        match command {
+            System(Dismiss) => self.set_prompt(PromptType::None),
+            Edit(Insert(digit)) => {
+                self.set_prompt(PromptType::None);
+                let file_name = digit
+                    .to_digit(10)
+                    .and_then(|number| usize::try_from(number).ok())
+                    .and_then(|number| number.checked_sub(1))
+                    .and_then(|idx| self.recent_files.get(idx))
+                    .map(str::to_string);
+                match file_name {
+                    Some(file_name) => self.open_confirmed(file_name),
+                    None => self.update_message(&format!("No recent file '{digit}'.")),
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Opens `file_name`, first asking to discard unsaved changes if there
+    /// are any.
+    fn open_confirmed(&mut self, file_name: String) {
+        if self.view.get_status().is_modified {
+            self.pending_open = Some(file_name);
+            self.set_prompt(PromptType::ConfirmOpen);
+        } else {
+            self.open(&file_name);
+        }
+    }
+
+    fn handle_alternate_file_command(&mut self) {
+        let alternate = self
+            .view
+            .get_file_path()
+            .and_then(Path::to_str)
+            .and_then(|path| alternate_file::find(path, &self.alternate_files));
+        let Some(alternate) = alternate else {
+            self.update_message("No alternate file found.");
+            return;
+        };
+        self.open_confirmed(alternate);
+    }
+
+    /// Cycles through the files given on the command line.
+    fn handle_next_file_command(&mut self) {
+        if self.file_arguments.len() < 2 {
+            self.update_message("No other files were given on the command line.");
+            return;
+        }
+        if self.view.get_status().is_modified {
+            self.update_message("Unsaved changes. Save before opening the next file.");
+            return;
+        }
+        self.file_argument_idx = self
+            .file_argument_idx
+            .saturating_add(1)
+            .checked_rem(self.file_arguments.len())
+            .unwrap_or(0);
+        let Some(argument) = self.file_arguments.get(self.file_argument_idx).cloned() else {
+            return;
+        };
+        let (file_name, _) = Self::parse_file_argument(&argument);
+        let position = format!(
+            "{}/{}",
+            self.file_argument_idx.saturating_add(1),
+            self.file_arguments.len()
+        );
+        match self.open_argument(&argument) {
+            Ok(()) => {
+                self.update_message(&format!("Opened {file_name} ({position})."));
+                self.report_whitespace_issues();
+            },
+            Err(err) => self.update_error(&format!(
+                "Could not open {file_name} ({position}): {err}"
+            )),
+        }
+    }
+
+    fn remember_recent_file(&mut self, file_name: &str) {
+        self.recent_files.add(file_name);
+        self.view.set_recent_files(self.recent_files.paths());
+    }
+
+    fn report_annotation_jump(&mut self, result: Option<bool>) {
+        match result {
+            None if !self.view.config().highlight_trailing_whitespace => {
+                self.update_message("Trailing whitespace is not highlighted.");
+            },
+            None => self.update_message("No trailing whitespace found."),
+            Some(true) => self.update_message("Wrapped around the document."),
+            Some(false) => {},
+        }
+    }
+
+    fn report_config_warnings(&mut self, warnings: &[String]) {
+        let Some(first) = warnings.first() else {
+            return;
+        };
+        let message = if warnings.len() > 1 {
+            format!(
+                "Config file: {first} (and {} more)",
+                warnings.len().saturating_sub(1)
+            )
+        } else {
+            format!("Config file: {first}")
+        };
+        self.update_warning(&message);
+    }
+
+    fn report_whitespace_issues(&mut self) {
+        if let Some(message) = self.view.analyze_whitespace().to_message() {
+            self.update_warning(&message);
+        }
+    }
+
+    fn process_command_during_mark(&mut self, command: Command) {
+        match command {
+            System(Dismiss) => self.set_prompt(PromptType::None),
+            Edit(Insert(name)) => {
+                let is_set = self.prompt_type == PromptType::SetMark;
+                self.set_prompt(PromptType::None);
+                if is_set {
+                    if self.view.set_mark(name) {
+                        self.update_message(&format!("Mark '{name}' set."));
+                    } else {
+                        self.update_message("Marks must be named a-z.");
+                    }
+                } else if !self.view.jump_to_mark(name) {
+                    self.update_message(&format!("Mark '{name}' not set."));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn process_command_during_palette(&mut self, command: Command) {
+        match command {
+            System(Dismiss) => self.set_prompt(PromptType::None),
+            Edit(InsertNewline) => {
+                let selected = self
+                    .palette_matches
+                    .get(self.command_list.selected())
+                    .copied();
+                self.set_prompt(PromptType::None);
+                if let Some(command) = selected {
+                    self.process_command(command);
+                }
+            },
+            Edit(edit_command) => self.edit_prompt(edit_command),
+            Move(Up) => self.command_list.select_previous(),
+            Move(Down) => self.command_list.select_next(),
+            _ => {},
+        }
+    }
+
+    /// Shows the named commands containing the typed text, ignoring case.
+    fn filter_palette(&mut self) {
+        let filter = self.command_bar.value().to_lowercase();
+        let (names, commands) = named_commands()
+            .into_iter()
+            .filter(|named| named.name.to_lowercase().contains(&filter))
+            .map(|named| (format!("{:<30} {}", named.name, named.keys), named.command))
+            .unzip();
+        self.palette_matches = commands;
+        self.command_list.set_items(names);
+        self.resize_command_list();
+        // Rows no longer covered by the shorter list must show the text again.
+        self.view.set_needs_redraw(true);
+    }
+
     fn update_message(&mut self, new_message: &str) {
         self.message_bar.update_message(new_message);
     }
 
+    fn update_warning(&mut self, new_message: &str) {
+        self.message_bar.update_warning(new_message);
+    }
+
+    fn update_error(&mut self, new_message: &str) {
+        self.message_bar.update_error(new_message);
+    }
+
+    /// Draws `message` right away, ahead of an operation that blocks the
+    /// event loop. The operation's own message replaces it afterwards.
+    fn show_busy(&mut self, message: &str) {
+        let Size { height, .. } = self.terminal_size;
+        if height == 0 || self.in_prompt() {
+            return;
+        }
+        self.update_message(message);
+        self.message_bar.render(self.renderer.as_mut(), height.saturating_sub(1));
+        let _ = self.renderer.execute();
+    }
+
+    fn show_busy_for_buffer(&mut self, message: &str) {
+        if self.view.get_file_summary().size > BUSY_THRESHOLD {
+            self.show_busy(message);
+        }
+    }
+
     fn in_prompt(&self) -> bool {
         !self.prompt_type.is_none()
     }
@@ -315,18 +1246,77 @@ impl Editor {
                 self.command_bar
                     .set_prompt("Search (Esc to cancel, Arrows to navigate): ");
             },
-            PromptType::None => self.message_bar.set_needs_redraw(true),
+            PromptType::SetMark => self.command_bar.set_prompt("Set mark (a-z): "),
+            PromptType::JumpToMark => self
+                .command_bar
+                .set_prompt("Jump to mark (a-z, ' for last position): "),
+            PromptType::ConfirmOpen => self
+                .command_bar
+                .set_prompt("Discard unsaved changes and open another file? (y/n) "),
+            PromptType::Open => self.command_bar.set_prompt("Open: "),
+            PromptType::OpenRecent => self.command_bar.set_prompt("Open recent file (1-9): "),
+            PromptType::CommandPalette => self.command_bar.set_prompt("Command: "),
+            PromptType::ExportHtml => self.command_bar.set_prompt("Export HTML as: "),
+            PromptType::GoTo => self.command_bar.set_prompt("Go to line[:column]: "),
+            PromptType::None => {
+                self.message_bar.set_needs_redraw(true);
+                if self.prompt_type == PromptType::CommandPalette {
+                    self.view.set_needs_redraw(true);
+                }
+            },
         }
         self.command_bar.clear_value();
         self.prompt_type = prompt_type;
+        if self.prompt_type == PromptType::CommandPalette {
+            self.filter_palette();
+        }
     }
 }
 
 impl Drop for Editor {
     fn drop(&mut self) {
-        let _ = Terminal::terminate();
+        let _ = self.renderer.terminate();
         if self.should_quit {
-            let _ = Terminal::print("Goodbye.\r\n");
+            let _ = self.renderer.print("Goodbye.\r\n");
+            let _ = self.renderer.execute();
+            if self.write_stdout
+                && let Err(err) = self.view.write_to(&mut stdout().lock())
+            {
+                eprintln!("{NAME}: Could not write to stdout: {err}");
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    use terminal::MemoryRenderer;
+
+    #[test]
+    fn draws_on_any_renderer() {
+        let renderer = MemoryRenderer::new(Size {
+            height: 5,
+            width: 40,
+        });
+        let rows = renderer.rows();
+        let arguments = Arguments::default();
+        let mut editor = Editor::with_renderer(&arguments, Box::new(renderer)).unwrap();
+        for character in ['h', 'i'] {
+            editor.evaluate_event(Event::Key(KeyEvent::new(
+                KeyCode::Char(character),
+                KeyModifiers::NONE,
+            )));
+            editor.refresh_status();
+        }
+        editor.refresh_screen();
+
+        let rows = rows.borrow();
+        assert_eq!(rows.len(), 5);
+        assert!(rows[0].starts_with("hi"), "{:?}", rows[0]);
+        assert!(rows[3].contains("(modified)"), "{:?}", rows[3]);
+    }
+}
@@ -0,0 +1,41 @@
+use std::fs::read_dir;
+
+/// Completes the last path component of `value` as far as all matching
+/// directory entries agree. A unique match that is a directory gets a
+/// trailing `/`. Returns `None` if nothing could be added.
+pub fn complete_path(value: &str) -> Option<String> {
+    let (dir, prefix) = value
+        .rfind('/')
+        .map_or(("", value), |idx| value.split_at(idx.saturating_add(1)));
+    let search_dir = if dir.is_empty() { "." } else { dir };
+    let show_hidden = prefix.starts_with('.');
+
+    let mut candidates: Vec<(String, bool)> = read_dir(search_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let is_dir = entry.path().is_dir();
+            Some((name, is_dir))
+        })
+        .filter(|(name, _)| name.starts_with(prefix) && (show_hidden || !name.starts_with('.')))
+        .collect();
+    candidates.sort();
+
+    let (first, _) = candidates.first()?;
+    let mut completion = first.clone();
+    for (name, _) in candidates.iter().skip(1) {
+        let common_len = completion
+            .char_indices()
+            .zip(name.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or_else(|| completion.len().min(name.len()), |((idx, _), _)| idx);
+        completion.truncate(common_len);
+    }
+    if let [(_, true)] = candidates.as_slice() {
+        completion.push('/');
+    }
+
+    let completed = format!("{dir}{completion}");
+    (completed.len() > value.len()).then_some(completed)
+}
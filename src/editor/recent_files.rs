@@ -0,0 +1,69 @@
+use std::{
+    env,
+    fs::{self, canonicalize},
+    path::PathBuf,
+};
+
+const MAX_RECENT_FILES: usize = 9;
+const STATE_FILE_NAME: &str = "recent_files";
+
+#[derive(Default)]
+pub struct RecentFiles {
+    paths: Vec<String>,
+}
+
+impl RecentFiles {
+    pub fn load() -> Self {
+        let paths = Self::state_file()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .take(MAX_RECENT_FILES)
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { paths }
+    }
+
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.paths.get(idx).map(String::as_str)
+    }
+
+    pub fn add(&mut self, file_name: &str) {
+        let path = canonicalize(file_name)
+            .map_or_else(|_| file_name.to_string(), |path| path.display().to_string());
+        self.paths.retain(|existing| *existing != path);
+        self.paths.insert(0, path);
+        self.paths.truncate(MAX_RECENT_FILES);
+        self.store();
+    }
+
+    fn store(&self) {
+        let Some(state_file) = Self::state_file() else {
+            return;
+        };
+        if let Some(parent) = state_file.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let mut contents = self.paths.join("\n");
+        contents.push('\n');
+        let _ = fs::write(state_file, contents);
+    }
+
+    fn state_file() -> Option<PathBuf> {
+        let state_dir = env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+            })?;
+        Some(state_dir.join("hecto").join(STATE_FILE_NAME))
+    }
+}
@@ -1,5 +1,5 @@
 
-use crossterm::style::Color;
+use crossterm::style::{self, Color};
 
 use super::super::AnnotationType;
 
@@ -8,7 +8,20 @@ pub struct Attribute {
     pub background: Option<Color>,
 }
 
+impl Attribute {
+    /// Drawn instead of the colors when they are disabled, so errors and
+    /// warnings still stand out from plain messages.
+    pub const fn without_colors(annotation_type: AnnotationType) -> Option<style::Attribute> {
+        match annotation_type {
+            AnnotationType::Error => Some(style::Attribute::Reverse),
+            AnnotationType::Warning => Some(style::Attribute::Bold),
+            _ => None,
+        }
+    }
+}
+
 impl From<AnnotationType> for Attribute {
+    #[allow(clippy::too_many_lines)]
     fn from(value: AnnotationType) -> Self {
         match value {
             AnnotationType::Match => Self {
@@ -43,6 +56,126 @@ impl From<AnnotationType> for Attribute {
                 }),
                 background: None,
             },
+            AnnotationType::Heading => Self {
+                foreground: Some(Color::Rgb {
+                    r: 100,
+                    g: 149,
+                    b: 237,
+                }),
+                background: None,
+            },
+            AnnotationType::Bold => Self {
+                foreground: Some(Color::Rgb {
+                    r: 255,
+                    g: 165,
+                    b: 0,
+                }),
+                background: None,
+            },
+            AnnotationType::Italic => Self {
+                foreground: Some(Color::Rgb {
+                    r: 218,
+                    g: 112,
+                    b: 214,
+                }),
+                background: None,
+            },
+            AnnotationType::Code => Self {
+                foreground: Some(Color::Rgb {
+                    r: 144,
+                    g: 238,
+                    b: 144,
+                }),
+                background: None,
+            },
+            AnnotationType::Error => Self {
+                foreground: Some(Color::Rgb {
+                    r: 220,
+                    g: 20,
+                    b: 60,
+                }),
+                background: None,
+            },
+            AnnotationType::Warning => Self {
+                foreground: Some(Color::Rgb {
+                    r: 255,
+                    g: 215,
+                    b: 0,
+                }),
+                background: None,
+            },
+            AnnotationType::TrailingWhitespace => Self {
+                foreground: None,
+                background: Some(Color::Rgb {
+                    r: 178,
+                    g: 34,
+                    b: 34,
+                }),
+            },
+            AnnotationType::MatchingBracket => Self {
+                foreground: None,
+                background: Some(Color::Rgb {
+                    r: 70,
+                    g: 130,
+                    b: 180,
+                }),
+            },
+            AnnotationType::UnmatchedBracket => Self {
+                foreground: Some(Color::Rgb {
+                    r: 255,
+                    g: 255,
+                    b: 255,
+                }),
+                background: Some(Color::Rgb {
+                    r: 220,
+                    g: 20,
+                    b: 60,
+                }),
+            },
+            AnnotationType::WordOccurrence => Self {
+                foreground: None,
+                background: Some(Color::Rgb {
+                    r: 80,
+                    g: 80,
+                    b: 80,
+                }),
+            },
+            AnnotationType::DiffAdded => Self {
+                foreground: None,
+                background: Some(Color::Rgb {
+                    r: 0,
+                    g: 95,
+                    b: 0,
+                }),
+            },
+            AnnotationType::DiffChanged => Self {
+                foreground: None,
+                background: Some(Color::Rgb {
+                    r: 0,
+                    g: 70,
+                    b: 120,
+                }),
+            },
+            AnnotationType::DiffRemoved => Self {
+                foreground: None,
+                background: Some(Color::Rgb {
+                    r: 150,
+                    g: 0,
+                    b: 0,
+                }),
+            },
+            AnnotationType::Clip => Self {
+                foreground: Some(Color::Rgb {
+                    r: 0,
+                    g: 0,
+                    b: 0,
+                }),
+                background: Some(Color::Rgb {
+                    r: 180,
+                    g: 180,
+                    b: 180,
+                }),
+            },
         }
     }
 }
@@ -1,18 +1,28 @@
 
 mod attribute;
+mod renderer;
 
 use crate::prelude::*;
 
-use std::io::{Error, Write, stdout};
+use std::{
+    env,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Error, IsTerminal, Write, stdout},
+    sync::{
+        Mutex, OnceLock, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
 use attribute::Attribute;
 use crossterm::{
     Command,
     cursor::{Hide, MoveTo, Show},
+    event::{DisableBracketedPaste, EnableBracketedPaste},
     queue,
     style::{
         Attribute::{Reset, Reverse},
-        Print, ResetColor, SetBackgroundColor, SetForegroundColor,
+        Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
     },
     terminal::{
         Clear, ClearType, DisableLineWrap, EnableLineWrap, EnterAlternateScreen,
@@ -20,23 +30,87 @@ use crossterm::{
     },
 };
 
-use super::{AnnotatedString, Position, Size};
+use super::{AnnotatedString, AnnotationType, Position, Size};
+pub use renderer::Renderer;
+#[cfg(test)]
+pub use renderer::MemoryRenderer;
+
+static IN_ALTERNATE_SCREEN: AtomicBool = AtomicBool::new(false);
+static LINE_WRAP_DISABLED: AtomicBool = AtomicBool::new(false);
+static BRACKETED_PASTE_ENABLED: AtomicBool = AtomicBool::new(false);
+static COLORS_DISABLED: AtomicBool = AtomicBool::new(false);
+/// Set by `draw_on_tty` when stdout is redirected, so the buffer can be
+/// written there on exit while the editor itself draws on the controlling
+/// terminal.
+static TTY_OUTPUT: OnceLock<Mutex<BufWriter<std::fs::File>>> = OnceLock::new();
 
+/// The crossterm terminal, which `Editor::new` renders to.
 pub struct Terminal;
 
 impl Terminal {
+    /// Alternate screen, line wrap control and bracketed paste are best
+    /// effort: terminals that reject them still get a usable, if degraded,
+    /// editor.
     pub fn initialize() -> Result<(), Error> {
+        // See https://no-color.org: any non-empty value disables colors.
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        COLORS_DISABLED.store(no_color, Ordering::Relaxed);
         enable_raw_mode()?;
-        Self::enter_alternate_screen()?;
-        Self::disable_line_wrap()?;
+        let entered = Self::enter_alternate_screen()
+            .and_then(|()| Self::execute())
+            .is_ok();
+        IN_ALTERNATE_SCREEN.store(entered, Ordering::Relaxed);
+        let disabled = Self::disable_line_wrap()
+            .and_then(|()| Self::execute())
+            .is_ok();
+        LINE_WRAP_DISABLED.store(disabled, Ordering::Relaxed);
+        let pasting = Self::queue_command(EnableBracketedPaste)
+            .and_then(|()| Self::execute())
+            .is_ok();
+        BRACKETED_PASTE_ENABLED.store(pasting, Ordering::Relaxed);
         Self::clear_screen()?;
         Self::execute()?;
         Ok(())
     }
 
+    /// Draws on the controlling terminal instead of stdout, which `--stdout`
+    /// keeps for the buffer. Does nothing if stdout is a terminal.
+    pub fn draw_on_tty() -> Result<(), Error> {
+        if !stdout().is_terminal() {
+            let tty = Self::open_tty()?;
+            let _ = TTY_OUTPUT.set(Mutex::new(BufWriter::new(tty)));
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn open_tty() -> Result<File, Error> {
+        OpenOptions::new().write(true).open("/dev/tty")
+    }
+
+    #[cfg(windows)]
+    fn open_tty() -> Result<File, Error> {
+        OpenOptions::new().read(true).write(true).open("CONOUT$")
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn open_tty() -> Result<File, Error> {
+        Err(Error::new(
+            std::io::ErrorKind::Unsupported,
+            "no controlling terminal to draw on while stdout is redirected",
+        ))
+    }
+
     pub fn terminate() -> Result<(), Error> {
-        Self::leave_alternate_screen()?;
-        Self::enable_line_wrap()?;
+        if IN_ALTERNATE_SCREEN.swap(false, Ordering::Relaxed) {
+            Self::leave_alternate_screen()?;
+        }
+        if LINE_WRAP_DISABLED.swap(false, Ordering::Relaxed) {
+            Self::enable_line_wrap()?;
+        }
+        if BRACKETED_PASTE_ENABLED.swap(false, Ordering::Relaxed) {
+            Self::queue_command(DisableBracketedPaste)?;
+        }
         Self::show_caret()?;
         Self::execute()?;
         disable_raw_mode()?;
@@ -98,25 +172,27 @@ impl Terminal {
         Ok(())
     }
     pub fn print_row(row: RowIdx, line_text: &str) -> Result<(), Error> {
-        Self::move_caret_to(Position { col: 0, row })?;
+        Self::move_caret_to(Position::new(row, 0))?;
         Self::clear_line()?;
         Self::print(line_text)?;
         Ok(())
     }
 
-    pub fn print_annotated_row(
+    /// Prints the strings one after another on `row`, e.g. a gutter and
+    /// then the text.
+    pub fn print_annotated_parts(
         row: usize,
-        annotated_string: &AnnotatedString,
+        annotated_strings: &[&AnnotatedString],
     ) -> Result<(), Error> {
-        Self::move_caret_to(Position { col: 0, row })?;
+        Self::move_caret_to(Position::new(row, 0))?;
         Self::clear_line()?;
 
-        annotated_string
-            .into_iter()
+        annotated_strings
+            .iter()
+            .flat_map(|annotated_string| annotated_string.into_iter())
             .try_for_each(|part| -> Result<(), Error> {
                 if let Some(annotation_type) = part.annotation_type {
-                    let attribute: Attribute = annotation_type.into();
-                    Self::set_attribute(&attribute)?;
+                    Self::set_attribute(annotation_type)?;
                 }
                 Self::print(part.string)?;
                 Self::reset_color()?;
@@ -125,7 +201,14 @@ impl Terminal {
         Ok(())
     }
 
-    fn set_attribute(attribute: &Attribute) -> Result<(), Error> {
+    fn set_attribute(annotation_type: AnnotationType) -> Result<(), Error> {
+        if COLORS_DISABLED.load(Ordering::Relaxed) {
+            if let Some(attribute) = Attribute::without_colors(annotation_type) {
+                Self::queue_command(SetAttribute(attribute))?;
+            }
+            return Ok(());
+        }
+        let attribute = Attribute::from(annotation_type);
         if let Some(foreground_color) = attribute.foreground {
             Self::queue_command(SetForegroundColor(foreground_color))?;
         }
@@ -136,6 +219,9 @@ impl Terminal {
     }
 
     fn reset_color() -> Result<(), Error> {
+        if COLORS_DISABLED.load(Ordering::Relaxed) {
+            return Self::queue_command(SetAttribute(Reset));
+        }
         Self::queue_command(ResetColor)?;
         Ok(())
     }
@@ -144,7 +230,7 @@ impl Terminal {
         let width = Self::size()?.width;
         Self::print_row(
             row,
-            &format!("{Reverse}{:width$.width$}{Reset}", line_text,),
+            &format!("{Reverse}{line_text:width$.width$}{Reset}"),
         )
     }
 
@@ -154,16 +240,22 @@ impl Terminal {
         let width = width_u16 as usize;
         #[allow(clippy::as_conversions)]
         let height = height_u16 as usize;
-        Ok(Size { width, height })
+        Ok(Size { height, width })
     }
 
     pub fn execute() -> Result<(), Error> {
-        stdout().flush()?;
+        match TTY_OUTPUT.get() {
+            Some(tty) => tty.lock().unwrap_or_else(PoisonError::into_inner).flush()?,
+            None => stdout().flush()?,
+        }
         Ok(())
     }
 
     fn queue_command<T: Command>(command: T) -> Result<(), Error> {
-        queue!(stdout(), command)?;
+        match TTY_OUTPUT.get() {
+            Some(tty) => queue!(tty.lock().unwrap_or_else(PoisonError::into_inner), command)?,
+            None => queue!(stdout(), command)?,
+        }
         Ok(())
     }
 }
@@ -0,0 +1,193 @@
+use crate::prelude::*;
+
+use std::io::Error;
+
+use super::{super::AnnotatedString, Position, Size, Terminal};
+
+/// The drawing surface the editor and its components render to.
+/// `Editor::new` draws on the `Terminal`; other front-ends, such as the
+/// in-memory one the tests use, go through `Editor::with_renderer`.
+pub trait Renderer {
+    fn initialize(&mut self) -> Result<(), Error>;
+
+    fn terminate(&mut self) -> Result<(), Error>;
+
+    fn move_caret_to(&mut self, position: Position) -> Result<(), Error>;
+
+    fn hide_caret(&mut self) -> Result<(), Error>;
+
+    fn show_caret(&mut self) -> Result<(), Error>;
+
+    fn set_title(&mut self, title: &str) -> Result<(), Error>;
+
+    fn print(&mut self, string: &str) -> Result<(), Error>;
+
+    /// Replaces `row` with `line_text`.
+    fn print_row(&mut self, row: RowIdx, line_text: &str) -> Result<(), Error>;
+
+    /// Replaces `row` with the strings one after another, in their
+    /// annotation colors.
+    fn print_annotated_parts(
+        &mut self,
+        row: RowIdx,
+        annotated_strings: &[&AnnotatedString],
+    ) -> Result<(), Error>;
+
+    fn print_annotated_row(
+        &mut self,
+        row: RowIdx,
+        annotated_string: &AnnotatedString,
+    ) -> Result<(), Error> {
+        self.print_annotated_parts(row, &[annotated_string])
+    }
+
+    /// Replaces `row` with `line_text` in reverse video, padded or cut to
+    /// the full width.
+    fn print_inverted_row(&mut self, row: RowIdx, line_text: &str) -> Result<(), Error>;
+
+    fn size(&self) -> Result<Size, Error>;
+
+    /// Flushes everything drawn since the last call.
+    fn execute(&mut self) -> Result<(), Error>;
+}
+
+impl Renderer for Terminal {
+    fn initialize(&mut self) -> Result<(), Error> {
+        Self::initialize()
+    }
+
+    fn terminate(&mut self) -> Result<(), Error> {
+        Self::terminate()
+    }
+
+    fn move_caret_to(&mut self, position: Position) -> Result<(), Error> {
+        Self::move_caret_to(position)
+    }
+
+    fn hide_caret(&mut self) -> Result<(), Error> {
+        Self::hide_caret()
+    }
+
+    fn show_caret(&mut self) -> Result<(), Error> {
+        Self::show_caret()
+    }
+
+    fn set_title(&mut self, title: &str) -> Result<(), Error> {
+        Self::set_title(title)
+    }
+
+    fn print(&mut self, string: &str) -> Result<(), Error> {
+        Self::print(string)
+    }
+
+    fn print_row(&mut self, row: RowIdx, line_text: &str) -> Result<(), Error> {
+        Self::print_row(row, line_text)
+    }
+
+    fn print_annotated_parts(
+        &mut self,
+        row: RowIdx,
+        annotated_strings: &[&AnnotatedString],
+    ) -> Result<(), Error> {
+        Self::print_annotated_parts(row, annotated_strings)
+    }
+
+    fn print_inverted_row(&mut self, row: RowIdx, line_text: &str) -> Result<(), Error> {
+        Self::print_inverted_row(row, line_text)
+    }
+
+    fn size(&self) -> Result<Size, Error> {
+        Self::size()
+    }
+
+    fn execute(&mut self) -> Result<(), Error> {
+        Self::execute()
+    }
+}
+
+/// Keeps the rows drawn in memory, for tests. The rows are shared, so they
+/// can still be read once the renderer is boxed and handed over.
+#[cfg(test)]
+pub struct MemoryRenderer {
+    size: Size,
+    rows: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+}
+
+#[cfg(test)]
+impl MemoryRenderer {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            rows: std::rc::Rc::new(std::cell::RefCell::new(vec![String::new(); size.height])),
+        }
+    }
+
+    pub fn rows(&self) -> std::rc::Rc<std::cell::RefCell<Vec<String>>> {
+        std::rc::Rc::clone(&self.rows)
+    }
+
+    fn set_row(&self, row: RowIdx, text: String) {
+        if let Some(slot) = self.rows.borrow_mut().get_mut(row) {
+            *slot = text;
+        }
+    }
+}
+
+#[cfg(test)]
+impl Renderer for MemoryRenderer {
+    fn initialize(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn terminate(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn move_caret_to(&mut self, _: Position) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn hide_caret(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn show_caret(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_title(&mut self, _: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn print(&mut self, _: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn print_row(&mut self, row: RowIdx, line_text: &str) -> Result<(), Error> {
+        self.set_row(row, line_text.into());
+        Ok(())
+    }
+
+    fn print_annotated_parts(
+        &mut self,
+        row: RowIdx,
+        annotated_strings: &[&AnnotatedString],
+    ) -> Result<(), Error> {
+        let text = annotated_strings.iter().map(ToString::to_string).collect();
+        self.set_row(row, text);
+        Ok(())
+    }
+
+    fn print_inverted_row(&mut self, row: RowIdx, line_text: &str) -> Result<(), Error> {
+        self.set_row(row, line_text.into());
+        Ok(())
+    }
+
+    fn size(&self) -> Result<Size, Error> {
+        Ok(self.size)
+    }
+
+    fn execute(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
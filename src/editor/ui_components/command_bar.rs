@@ -4,7 +4,7 @@ use crate::prelude::*;
 use std::{cmp::min, io::Error};
 
 use super::{
-    super::{Line, Size, Terminal, command::Edit},
+    super::{AnnotatedString, AnnotationType, Line, Renderer, Size, command::Edit},
     UIComponent,
 };
 
@@ -12,6 +12,9 @@ use super::{
 pub struct CommandBar {
     prompt: String,
     value: Line,
+    value_annotation: Option<AnnotationType>,
+    /// Shown after the value but not part of it, like a match count.
+    suffix: String,
     needs_redraw: bool,
     size: Size,
 }
@@ -19,6 +22,8 @@ pub struct CommandBar {
 impl CommandBar {
     pub fn handle_edit_command(&mut self, command: Edit) {
         match command {
+            // A literal tab is never part of a file name or search query.
+            Edit::Insert('\t') => return,
             Edit::Insert(character) => self.value.append_char(character),
             Edit::DeleteBackward => self.value.delete_last(),
             _ => {},
@@ -43,10 +48,31 @@ impl CommandBar {
         self.set_needs_redraw(true);
     }
 
+    pub fn set_value(&mut self, value: &str) {
+        self.value = Line::from(value);
+        self.set_needs_redraw(true);
+    }
+
     pub fn clear_value(&mut self) {
         self.value = Line::default();
+        self.value_annotation = None;
+        self.suffix.clear();
         self.set_needs_redraw(true);
     }
+
+    pub fn set_suffix(&mut self, suffix: &str) {
+        if self.suffix != suffix {
+            self.suffix = suffix.to_string();
+            self.set_needs_redraw(true);
+        }
+    }
+
+    pub fn set_value_annotation(&mut self, annotation_type: Option<AnnotationType>) {
+        if self.value_annotation != annotation_type {
+            self.value_annotation = annotation_type;
+            self.set_needs_redraw(true);
+        }
+    }
 }
 impl UIComponent for CommandBar {
     fn set_needs_redraw(&mut self, value: bool) {
@@ -58,7 +84,19 @@ impl UIComponent for CommandBar {
     fn set_size(&mut self, size: Size) {
         self.size = size;
     }
-    fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error> {
+    fn draw(&mut self, renderer: &mut dyn Renderer, origin_row: RowIdx) -> Result<(), Error> {
+        // The suffix is dropped rather than scrolling the value to fit it.
+        let full_width = self.prompt.len().saturating_add(self.value.width());
+        let suffix = if self.suffix.is_empty()
+            || full_width
+                .saturating_add(self.suffix.len())
+                .saturating_add(1)
+                > self.size.width
+        {
+            String::new()
+        } else {
+            format!(" {}", self.suffix)
+        };
         let area_for_value = self.size.width.saturating_sub(self.prompt.len());
         let value_end = self.value.width();
         let value_start = value_end.saturating_sub(area_for_value);
@@ -67,11 +105,16 @@ impl UIComponent for CommandBar {
             self.prompt,
             self.value.get_visible_graphemes(value_start..value_end)
         );
-        let to_print = if message.len() <= self.size.width {
-            message
+        let mut to_print = if message.len() <= self.size.width {
+            AnnotatedString::from(&format!("{message}{suffix}"))
         } else {
-            String::new()
+            AnnotatedString::default()
         };
-        Terminal::print_row(origin_row, &to_print)
+        if let Some(annotation_type) = self.value_annotation
+            && !to_print.is_empty()
+        {
+            to_print.add_annotation(annotation_type, self.prompt.len(), message.len());
+        }
+        renderer.print_annotated_row(origin_row, &to_print)
     }
 }
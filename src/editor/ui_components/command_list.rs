@@ -0,0 +1,85 @@
+use crate::prelude::*;
+
+use std::io::Error;
+
+use super::{
+    super::{Renderer, Size},
+    UIComponent,
+};
+
+/// A list of candidates drawn above the command bar, with one selected.
+#[derive(Default)]
+pub struct CommandList {
+    items: Vec<String>,
+    selected: usize,
+    needs_redraw: bool,
+    size: Size,
+}
+
+impl CommandList {
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        self.selected = 0;
+        self.set_needs_redraw(true);
+    }
+
+    pub const fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected.saturating_add(1) < self.items.len() {
+            self.selected = self.selected.saturating_add(1);
+            self.set_needs_redraw(true);
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.selected > 0 {
+            self.selected = self.selected.saturating_sub(1);
+            self.set_needs_redraw(true);
+        }
+    }
+
+    /// The number of rows the list needs, at most `max_height`.
+    pub fn height_within(&self, max_height: usize) -> usize {
+        self.items.len().min(max_height)
+    }
+}
+
+impl UIComponent for CommandList {
+    fn set_needs_redraw(&mut self, value: bool) {
+        self.needs_redraw = value;
+    }
+
+    fn needs_redraw(&self) -> bool {
+        self.needs_redraw
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, renderer: &mut dyn Renderer, origin_row: RowIdx) -> Result<(), Error> {
+        let Size { height, width } = self.size;
+        // Keep the selection visible when there are more items than rows.
+        let first = self.selected.saturating_add(1).saturating_sub(height);
+        for (row, (idx, item)) in self
+            .items
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(height)
+            .enumerate()
+        {
+            let text = format!(" {item:width$.width$}", width = width.saturating_sub(1));
+            let at = origin_row.saturating_add(row);
+            if idx == self.selected {
+                renderer.print_inverted_row(at, &text)?;
+            } else {
+                renderer.print_row(at, &text)?;
+            }
+        }
+        Ok(())
+    }
+}
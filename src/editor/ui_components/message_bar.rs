@@ -6,42 +6,108 @@ use std::{
     time::{Duration, Instant},
 };
 
-use super::{super::Terminal, UIComponent};
+use super::{
+    super::{AnnotatedString, AnnotationType, Renderer},
+    UIComponent,
+};
 
 const DEFAULT_DURATION: Duration = Duration::new(5, 0);
 
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    const fn annotation_type(self) -> Option<AnnotationType> {
+        match self {
+            Self::Info => None,
+            Self::Warning => Some(AnnotationType::Warning),
+            Self::Error => Some(AnnotationType::Error),
+        }
+    }
+}
+
 struct Message {
     text: String,
+    severity: Severity,
     time: Instant,
+    /// How long the message is shown; `None` keeps it until it is
+    /// dismissed or replaced.
+    duration: Option<Duration>,
 }
 
 impl Default for Message {
     fn default() -> Self {
         Self {
             text: String::new(),
+            severity: Severity::default(),
             time: Instant::now(),
+            duration: Some(DEFAULT_DURATION),
         }
     }
 }
 
 impl Message {
     fn is_expired(&self) -> bool {
-        Instant::now().duration_since(self.time) > DEFAULT_DURATION
+        self.duration
+            .is_some_and(|duration| Instant::now().duration_since(self.time) > duration)
     }
 }
 
-#[derive(Default)]
 pub struct MessageBar {
     current_message: Message,
     needs_redraw: bool,
     cleared_after_expiry: bool,
+    duration: Duration,
+}
+
+impl Default for MessageBar {
+    fn default() -> Self {
+        Self {
+            current_message: Message::default(),
+            needs_redraw: false,
+            cleared_after_expiry: false,
+            duration: DEFAULT_DURATION,
+        }
+    }
 }
 
 impl MessageBar {
     pub fn update_message(&mut self, new_message: &str) {
+        self.show(new_message, Severity::Info, Some(self.duration));
+    }
+
+    pub fn update_warning(&mut self, new_message: &str) {
+        self.show(new_message, Severity::Warning, Some(self.duration));
+    }
+
+    /// Shows an error that stays until `dismiss_sticky` or the next
+    /// message, so it isn't missed.
+    pub fn update_error(&mut self, new_message: &str) {
+        self.show(new_message, Severity::Error, None);
+    }
+
+    pub fn dismiss_sticky(&mut self) {
+        if self.current_message.duration.is_none() {
+            self.update_message("");
+        }
+    }
+
+    /// How long messages posted from now on are shown.
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    fn show(&mut self, new_message: &str, severity: Severity, duration: Option<Duration>) {
         self.current_message = Message {
             text: new_message.to_string(),
+            severity,
             time: Instant::now(),
+            duration,
         };
         self.cleared_after_expiry = false;
         self.set_needs_redraw(true);
@@ -58,18 +124,22 @@ impl UIComponent for MessageBar {
 
     fn set_size(&mut self, _: Size) {}
 
-    fn draw(&mut self, origin_y: RowIdx) -> Result<(), Error> {
+    fn draw(&mut self, renderer: &mut dyn Renderer, origin_y: RowIdx) -> Result<(), Error> {
         if self.current_message.is_expired() {
             self.cleared_after_expiry = true;
         }
 
-        let message = if self.current_message.is_expired() {
-            ""
-        } else {
-            &self.current_message.text
-        };
-
-        Terminal::print_row(origin_y, message)?;
-        Ok(())
+        if self.current_message.is_expired() {
+            return renderer.print_row(origin_y, "");
+        }
+        let message = &self.current_message.text;
+        match self.current_message.severity.annotation_type() {
+            Some(annotation_type) => {
+                let mut annotated_message = AnnotatedString::from(message);
+                annotated_message.add_annotation(annotation_type, 0, message.len());
+                renderer.print_annotated_row(origin_y, &annotated_message)
+            },
+            None => renderer.print_row(origin_y, message),
+        }
     }
 }
@@ -1,12 +1,14 @@
 
 mod command_bar;
+mod command_list;
 mod message_bar;
 mod status_bar;
 mod ui_component;
 mod view;
 
 pub use command_bar::CommandBar;
+pub use command_list::CommandList;
 pub use message_bar::MessageBar;
 pub use status_bar::StatusBar;
 pub use ui_component::UIComponent;
-pub use view::View;
+pub use view::{LineEnding, SearchCase, SearchScroll, View};
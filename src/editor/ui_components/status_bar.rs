@@ -3,18 +3,70 @@ use crate::prelude::*;
 use std::io::Error;
 
 use super::{
-    super::{DocumentStatus, Size, Terminal},
+    super::{DocumentStatus, Renderer, Size},
     UIComponent,
 };
 
+/// Reproduces the built-in layout; see `StatusBar::expand`.
+const DEFAULT_FORMAT: &str = "%f - %L lines %m%=%r%t | %l/%L";
+
 #[derive(Default)]
 pub struct StatusBar {
     current_status: DocumentStatus,
     needs_redraw: bool,
     size: Size,
+    format: Option<String>,
 }
 
 impl StatusBar {
+    /// Uses `format` instead of the default layout from now on.
+    pub fn set_format(&mut self, format: &str) {
+        self.format = Some(format.into());
+        self.set_needs_redraw(true);
+    }
+
+    /// Expands the placeholders of `template`: `%f` file name and flags,
+    /// `%l` line, `%L` line count, `%c` column, `%m` modified indicator,
+    /// `%t` file type, `%p` percent through the file, `%r` modes followed
+    /// by a space (if any) and `%%` a percent sign. Anything else is kept
+    /// as it is. The part after `%=` is returned separately to be aligned
+    /// right.
+    fn expand(&self, template: &str) -> (String, String) {
+        let status = &self.current_status;
+        let mut left = String::new();
+        let mut right: Option<String> = None;
+        let mut chars = template.chars().peekable();
+        while let Some(ch) = chars.next() {
+            let expansion = match (ch, chars.peek()) {
+                ('%', Some('f')) => {
+                    format!("{}{}", status.file_name, status.file_flags_to_string())
+                },
+                ('%', Some('l')) => status.current_line_idx.saturating_add(1).to_string(),
+                ('%', Some('L')) => status.total_lines.to_string(),
+                ('%', Some('c')) => status.column_to_string(),
+                ('%', Some('m')) => status.modified_indicator_to_string(),
+                ('%', Some('t')) => status.file_type.to_string(),
+                ('%', Some('p')) => status.percent_to_string(),
+                ('%', Some('r')) => {
+                    let modes = status.modes_to_string();
+                    if modes.is_empty() { modes } else { format!("{modes} ") }
+                },
+                ('%', Some('%')) => String::from("%"),
+                ('%', Some('=')) if right.is_none() => {
+                    chars.next();
+                    right = Some(String::new());
+                    continue;
+                },
+                _ => {
+                    right.as_mut().unwrap_or(&mut left).push(ch);
+                    continue;
+                },
+            };
+            chars.next();
+            right.as_mut().unwrap_or(&mut left).push_str(&expansion);
+        }
+        (left, right.unwrap_or_default())
+    }
     pub fn update_status(&mut self, new_status: DocumentStatus) {
         if self.current_status != new_status {
             self.current_status = new_status;
@@ -35,28 +87,22 @@ impl UIComponent for StatusBar {
         self.size = size;
     }
 
-    fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error> {
-        let line_count = self.current_status.line_count_to_string();
-        let modified_indicator = self.current_status.modified_indicator_to_string();
-        let beginning = format!(
-            "{} - {} {}",
-            self.current_status.file_name, line_count, modified_indicator
-        );
-
-        let position_indicator = self.current_status.position_indicator_to_string();
-        let right_indicator = format!("{} | {}", self.current_status.file_type, position_indicator);
-
+    fn draw(&mut self, renderer: &mut dyn Renderer, origin_row: RowIdx) -> Result<(), Error> {
+        let format = self.format.as_deref().unwrap_or(DEFAULT_FORMAT);
+        let (beginning, right_indicator) = self.expand(format);
         let remainder_len = self.size.width.saturating_sub(beginning.len());
 
         let status = format!("{beginning}{right_indicator:>remainder_len$}");
 
         let to_print = if status.len() <= self.size.width {
             status
+        } else if right_indicator.len() <= self.size.width {
+            format!("{right_indicator:>width$}", width = self.size.width)
         } else {
             String::new()
         };
 
-        Terminal::print_inverted_row(origin_row, &to_print)?;
+        renderer.print_inverted_row(origin_row, &to_print)?;
         Ok(())
     }
 }
@@ -3,6 +3,8 @@ use crate::prelude::*;
 
 use std::io::Error;
 
+use super::super::Renderer;
+
 pub trait UIComponent {
     fn set_needs_redraw(&mut self, value: bool);
 
@@ -15,9 +17,9 @@ pub trait UIComponent {
 
     fn set_size(&mut self, size: Size);
 
-    fn render(&mut self, origin_row: RowIdx) {
+    fn render(&mut self, renderer: &mut dyn Renderer, origin_row: RowIdx) {
         if self.needs_redraw() {
-            if let Err(err) = self.draw(origin_row) {
+            if let Err(err) = self.draw(renderer, origin_row) {
                 #[cfg(debug_assertions)]
                 {
                     panic!("Could not render component: {err:?}");
@@ -32,5 +34,5 @@ pub trait UIComponent {
         }
     }
 
-    fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error>;
+    fn draw(&mut self, renderer: &mut dyn Renderer, origin_row: RowIdx) -> Result<(), Error>;
 }
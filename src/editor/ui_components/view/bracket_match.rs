@@ -0,0 +1,11 @@
+use crate::prelude::*;
+
+/// What `View::find_matching_bracket` found for the grapheme at the caret.
+#[derive(Clone, Copy)]
+pub enum BracketMatch {
+    /// The caret is on a bracket whose partner is at the given location.
+    Found(Location),
+    /// The caret is on a bracket without a partner.
+    Unbalanced,
+    NotOnBracket,
+}
@@ -1,29 +1,221 @@
-use crate::{editor::annotated_string::AnnotatedString, prelude::*};
+use crate::{
+    editor::{annotated_string::AnnotatedString, editorconfig},
+    prelude::*,
+};
 
 use std::{
-    fs::{File, read_to_string},
-    io::{Error, Write},
+    borrow::Cow,
+    cell::OnceCell,
+    fmt::Write as _,
+    fs::{File, read, read_to_string},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{Error, ErrorKind, Write},
     ops::Range,
+    path::Path,
 };
 
-use super::{FileInfo, Highlighter, Line};
+use super::{
+    BracketMatch, FileInfo, Highlighter, html, Line, LineDiff, LineEnding, TabStyle, WhitespaceIssues,
+    diff_lines,
+};
 #[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Buffer {
     lines: Vec<Line>,
     file_info: FileInfo,
+    line_ending: LineEnding,
+    missing_final_newline: bool,
+    tab_style: TabStyle,
+    read_only: bool,
+    lossy: bool,
     dirty: bool,
+    /// Line hashes as of the last load or save, to tell changed lines.
+    baseline: Vec<u64>,
+    /// The diff against `baseline`, kept until the next edit, load or save.
+    changes: OnceCell<LineDiff>,
+    /// What `.editorconfig` files say about this file, as config settings.
+    project_settings: Vec<(String, String)>,
 }
 impl Buffer {
+    /// Whether the buffer was edited since it was loaded or last saved.
+    /// There is no undo, so this never goes back to `false` on its own:
+    /// reverting an edit by hand still leaves the buffer dirty until saved.
     pub const fn is_dirty(&self) -> bool {
         self.dirty
     }
 
+    pub const fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub const fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Whether invalid UTF-8 was replaced on load, so the contents on
+    /// screen no longer match the file on disk.
+    pub const fn is_lossy(&self) -> bool {
+        self.lossy
+    }
+
     pub const fn get_file_info(&self) -> &FileInfo {
         &self.file_info
     }
 
+    pub fn set_tab_style(&mut self, tab_style: TabStyle) {
+        self.tab_style = tab_style;
+        for line in &mut self.lines {
+            line.set_tab_style(tab_style);
+        }
+    }
+
+    pub const fn get_line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Line breaks are written as `line_ending` from now on.
+    pub const fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    /// Whether saving ends the last line with a line break.
+    pub const fn set_final_newline(&mut self, final_newline: bool) {
+        self.missing_final_newline = !final_newline;
+    }
+
+    /// An empty last line gets its line break even without a final
+    /// newline, as it would not be there after loading the file again.
+    fn writes_final_newline(&self) -> bool {
+        !self.missing_final_newline || self.lines.last().is_some_and(|line| line.is_empty())
+    }
+
+    pub fn project_settings(&self) -> &[(String, String)] {
+        &self.project_settings
+    }
+
+    /// The number of bytes the buffer would occupy if saved now.
+    pub fn byte_count(&self) -> usize {
+        let line_breaks = if self.writes_final_newline() {
+            self.lines.len()
+        } else {
+            self.lines.len().saturating_sub(1)
+        };
+        self.lines
+            .iter()
+            .map(|line| line.len())
+            .sum::<usize>()
+            .saturating_add(line_breaks.saturating_mul(self.line_ending.len()))
+    }
+
     pub fn grapheme_count(&self, idx: LineIdx) -> GraphemeIdx {
-        self.lines.get(idx).map_or(0, |line| line.grapheme_count())
+        self.lines.get(idx).map_or(0, Line::grapheme_count)
+    }
+
+    pub fn byte_idx_to_grapheme_idx(&self, idx: LineIdx, byte_idx: ByteIdx) -> GraphemeIdx {
+        self.lines
+            .get(idx)
+            .and_then(|line| line.byte_idx_to_grapheme_idx(byte_idx))
+            .unwrap_or(0)
+    }
+
+    /// The grapheme cluster at `at`, or `None` past the end of its line or
+    /// the buffer.
+    pub fn grapheme_at(&self, at: Location) -> Option<&str> {
+        self.lines
+            .get(at.line_idx)
+            .and_then(|line| line.grapheme_at(at.grapheme_idx))
+    }
+
+    pub fn is_bracket_at(&self, at: Location) -> bool {
+        self.grapheme_at(at).and_then(bracket_pair).is_some()
+    }
+
+    /// The word at or just before `at`, with the location it starts at.
+    pub fn word_at(&self, at: Location) -> Option<(&str, Location)> {
+        let line = self.lines.get(at.line_idx)?;
+        let graphemes = line.word_at(at.grapheme_idx)?;
+        let start = line.grapheme_idx_to_byte_idx(graphemes.start);
+        let end = line.byte_idx_after(graphemes.start, graphemes.len());
+        Some((
+            line.get(start..end)?,
+            Location {
+                line_idx: at.line_idx,
+                grapheme_idx: graphemes.start,
+            },
+        ))
+    }
+
+    /// Scans from the bracket at `at` to its partner, skipping nested pairs
+    /// of the same kind. Brackets in strings or comments are not special.
+    pub fn find_matching_bracket(&self, at: Location) -> BracketMatch {
+        let Some((open, close, forward)) = self.grapheme_at(at).and_then(bracket_pair) else {
+            return BracketMatch::NotOnBracket;
+        };
+        let Some(line) = self.lines.get(at.line_idx) else {
+            return BracketMatch::NotOnBracket;
+        };
+        let start = line.grapheme_idx_to_byte_idx(at.grapheme_idx);
+        let (opening, closing) = if forward { (open, close) } else { (close, open) };
+        let mut depth = 0_usize;
+        let mut scan = |line_idx: LineIdx, byte_idx: ByteIdx, ch: char| {
+            if ch == opening {
+                depth = depth.saturating_add(1);
+            } else if ch == closing {
+                if depth == 0 {
+                    return Some(Location {
+                        line_idx,
+                        grapheme_idx: self.byte_idx_to_grapheme_idx(line_idx, byte_idx),
+                    });
+                }
+                depth = depth.saturating_sub(1);
+            }
+            None
+        };
+        let found = if forward {
+            let after = start.saturating_add(open.len_utf8());
+            line[after..]
+                .char_indices()
+                .find_map(|(idx, ch)| scan(at.line_idx, after.saturating_add(idx), ch))
+                .or_else(|| {
+                    self.lines
+                        .iter()
+                        .enumerate()
+                        .skip(at.line_idx.saturating_add(1))
+                        .find_map(|(line_idx, line)| {
+                            line.char_indices()
+                                .find_map(|(idx, ch)| scan(line_idx, idx, ch))
+                        })
+                })
+        } else {
+            line[..start]
+                .char_indices()
+                .rev()
+                .find_map(|(idx, ch)| scan(at.line_idx, idx, ch))
+                .or_else(|| {
+                    self.lines[..at.line_idx]
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .find_map(|(line_idx, line)| {
+                            line.char_indices()
+                                .rev()
+                                .find_map(|(idx, ch)| scan(line_idx, idx, ch))
+                        })
+                })
+        };
+        found.map_or(BracketMatch::Unbalanced, BracketMatch::Found)
+    }
+
+    pub fn indentation(&self, idx: LineIdx) -> &str {
+        self.lines.get(idx).map_or("", |line| {
+            &line[..line.len().saturating_sub(line.trim_start().len())]
+        })
+    }
+
+    pub fn is_blank(&self, idx: LineIdx) -> bool {
+        self.lines
+            .get(idx)
+            .is_some_and(|line| line.trim().is_empty())
     }
 
     pub fn width_until(&self, idx: LineIdx, until: GraphemeIdx) -> GraphemeIdx {
@@ -31,6 +223,19 @@ impl Buffer {
             .get(idx)
             .map_or(0, |line| line.width_until(until))
     }
+
+    pub fn grapheme_idx_at_width(&self, idx: LineIdx, width: ColIdx) -> GraphemeIdx {
+        self.lines
+            .get(idx)
+            .map_or(0, |line| line.grapheme_idx_at_width(width))
+    }
+
+    pub fn grapheme_idx_near_width(&self, idx: LineIdx, width: ColIdx) -> GraphemeIdx {
+        self.lines
+            .get(idx)
+            .map_or(0, |line| line.grapheme_idx_near_width(width))
+    }
+
     pub fn get_highlighted_substring(
         &self,
         line_idx: LineIdx,
@@ -41,6 +246,33 @@ impl Buffer {
             line.get_annotated_visible_substr(range, Some(&highlighter.get_annotations(line_idx)))
         })
     }
+    /// Renders the whole buffer as an HTML page, with a `<span>` per
+    /// annotated part. All lines must have been highlighted already.
+    pub fn to_html(&self, highlighter: &Highlighter) -> String {
+        let mut body = String::new();
+        for (idx, line) in self.lines.iter().enumerate() {
+            let mut annotated_string = AnnotatedString::from(line);
+            for annotation in highlighter.get_annotations(idx) {
+                annotated_string.add_annotation(
+                    annotation.annotation_type,
+                    annotation.start,
+                    annotation.end,
+                );
+            }
+            for part in &annotated_string {
+                if let Some(annotation_type) = part.annotation_type {
+                    let _ = write!(body, "<span class=\"{}\">", html::css_class(annotation_type));
+                    html::push_escaped(&mut body, part.string);
+                    body.push_str("</span>");
+                } else {
+                    html::push_escaped(&mut body, part.string);
+                }
+            }
+            body.push('\n');
+        }
+        html::document(&self.file_info.to_string(), &body)
+    }
+
     pub fn highlight(&self, idx: LineIdx, highlighter: &mut Highlighter) {
         if let Some(line) = self.lines.get(idx) {
             highlighter.highlight(idx, line);
@@ -48,18 +280,112 @@ impl Buffer {
     }
     pub fn load(file_name: &str) -> Result<Self, Error> {
         let contents = read_to_string(file_name)?;
+        Ok(Self {
+            file_info: FileInfo::from(file_name),
+            project_settings: editorconfig::settings_for(Path::new(file_name)),
+            ..Self::from_text(&contents)
+        })
+    }
+
+    /// Like `load`, but invalid UTF-8 is replaced with U+FFFD instead of
+    /// failing. Such a buffer is read-only and can only be saved to a new
+    /// file, as writing it back would corrupt the original.
+    pub fn load_lossy(file_name: &str) -> Result<Self, Error> {
+        let bytes = read(file_name)?;
+        let contents = String::from_utf8_lossy(&bytes);
+        let lossy = matches!(contents, Cow::Owned(_));
+        Ok(Self {
+            file_info: FileInfo::from(file_name),
+            project_settings: editorconfig::settings_for(Path::new(file_name)),
+            read_only: lossy,
+            lossy,
+            ..Self::from_text(&contents)
+        })
+    }
+
+    /// Compares the lines with the file on disk, read afresh.
+    pub fn diff_against_file(&self) -> Result<LineDiff, Error> {
+        let Some(path) = self.file_info.get_path() else {
+            return Err(Error::new(ErrorKind::NotFound, "the buffer has no file"));
+        };
+        let on_disk = Self::from_text(&read_to_string(path)?);
+        let old: Vec<&str> = on_disk.lines.iter().map(|line| &**line).collect();
+        let new: Vec<&str> = self.lines.iter().map(|line| &**line).collect();
+        Ok(diff_lines(&old, &new))
+    }
+
+    /// Builds an unnamed, clean buffer without touching the filesystem.
+    pub fn from_text(contents: &str) -> Self {
         let mut lines = Vec::new();
         for value in contents.lines() {
             lines.push(Line::from(value));
         }
-        Ok(Self {
+        Self {
+            baseline: Self::line_hashes(&lines),
             lines,
-            file_info: FileInfo::from(file_name),
+            file_info: FileInfo::default(),
+            line_ending: LineEnding::detect(contents),
+            missing_final_newline: !contents.is_empty() && !contents.ends_with('\n'),
+            tab_style: TabStyle::default(),
+            read_only: false,
+            lossy: false,
             dirty: false,
-        })
+            changes: OnceCell::new(),
+            project_settings: Vec::new(),
+        }
+    }
+
+    fn line_hashes(lines: &[Line]) -> Vec<u64> {
+        lines
+            .iter()
+            .map(|line| {
+                let mut hasher = DefaultHasher::new();
+                (**line).hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect()
+    }
+
+    /// How the lines differ from when the buffer was loaded or last saved.
+    pub fn changes_since_baseline(&self) -> &LineDiff {
+        self.changes
+            .get_or_init(|| diff_lines(&self.baseline, &Self::line_hashes(&self.lines)))
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+        self.changes.take();
+    }
+
+    fn mark_clean(&mut self) {
+        self.dirty = false;
+        self.baseline = Self::line_hashes(&self.lines);
+        self.changes.take();
+    }
+
+    pub fn analyze_whitespace(&self) -> WhitespaceIssues {
+        let mut issues = WhitespaceIssues::default();
+        let mut indents_with_tabs = false;
+        let mut indents_with_spaces = false;
+        for line in &self.lines {
+            let indentation = &line[..line.len().saturating_sub(line.trim_start().len())];
+            indents_with_tabs |= indentation.contains('\t');
+            indents_with_spaces |= indentation.contains(' ');
+            issues.mixed_indentation = indents_with_tabs && indents_with_spaces;
+            issues.trailing_whitespace |= line.ends_with(char::is_whitespace);
+            if issues.is_complete() {
+                break;
+            }
+        }
+        issues
     }
 
-    pub fn search_forward(&self, query: &str, from: Location) -> Option<Location> {
+    pub fn search_forward(
+        &self,
+        query: &str,
+        from: Location,
+        ignore_case: bool,
+    ) -> Option<Location> {
         if query.is_empty() {
             return None;
         }
@@ -78,7 +404,7 @@ impl Buffer {
             } else {
                 0
             };
-            if let Some(grapheme_idx) = line.search_forward(query, from_grapheme_idx) {
+            if let Some(grapheme_idx) = line.search_forward(query, from_grapheme_idx, ignore_case) {
                 return Some(Location {
                     grapheme_idx,
                     line_idx,
@@ -88,10 +414,50 @@ impl Buffer {
         None
     }
 
-    pub fn search_backward(&self, query: &str, from: Location) -> Option<Location> {
+    /// Counts the matches of `query` before `at` and in total, giving up
+    /// once `limit` matches were seen. Returns both counts and whether
+    /// the limit cut the count short.
+    pub fn count_matches(
+        &self,
+        query: &str,
+        at: Location,
+        ignore_case: bool,
+        limit: usize,
+    ) -> (usize, usize, bool) {
+        let (mut before, mut total) = (0, 0);
+        for (line_idx, line) in self.lines.iter().enumerate() {
+            for (_, grapheme_idx) in line.find_all(query, 0..line.len(), ignore_case) {
+                if total >= limit {
+                    return (before, total, true);
+                }
+                if (line_idx, grapheme_idx) < (at.line_idx, at.grapheme_idx) {
+                    before = before.saturating_add(1);
+                }
+                total = total.saturating_add(1);
+            }
+        }
+        (before, total, false)
+    }
+
+    pub fn search_backward(
+        &self,
+        query: &str,
+        from: Location,
+        ignore_case: bool,
+    ) -> Option<Location> {
         if query.is_empty() {
             return None;
         }
+        // A location past the last line searches from the end of the last line,
+        // so matches on that line are found first instead of after wrapping.
+        let from = if from.line_idx >= self.lines.len() {
+            Location {
+                line_idx: self.lines.len().saturating_sub(1),
+                grapheme_idx: self.grapheme_count(self.lines.len().saturating_sub(1)),
+            }
+        } else {
+            from
+        };
         let mut is_first = true;
         for (line_idx, line) in self
             .lines
@@ -113,7 +479,9 @@ impl Buffer {
             } else {
                 line.grapheme_count()
             };
-            if let Some(grapheme_idx) = line.search_backward(query, from_grapheme_idx) {
+            if let Some(grapheme_idx) =
+                line.search_backward(query, from_grapheme_idx, ignore_case)
+            {
                 return Some(Location {
                     grapheme_idx,
                     line_idx,
@@ -122,12 +490,16 @@ impl Buffer {
         }
         None
     }
+    // Opening the path writes through a symlink to its target, so the link
+    // itself is kept.
     fn save_to_file(&self, file_info: &FileInfo) -> Result<(), Error> {
-        if let Some(file_path) = &file_info.get_path() {
-            let mut file = File::create(file_path)?;
-            for line in &self.lines {
-                writeln!(file, "{line}")?;
-            }
+        if let Some(file_path) = file_info.get_path() {
+            let mut file = if self.lossy {
+                File::create_new(file_path)?
+            } else {
+                File::create(file_path)?
+            };
+            self.write_to(&mut file)?;
         } else {
             #[cfg(debug_assertions)]
             {
@@ -137,17 +509,40 @@ impl Buffer {
         Ok(())
     }
 
+    /// Writes the contents exactly as `save` would put them on disk.
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<(), Error> {
+        let final_newline = self.writes_final_newline();
+        for (idx, line) in self.lines.iter().enumerate() {
+            write!(writer, "{line}")?;
+            let is_last = idx.saturating_add(1) == self.lines.len();
+            if !is_last || final_newline {
+                write!(writer, "{}", self.line_ending)?;
+            }
+        }
+        writer.flush()
+    }
+
     pub fn save_as(&mut self, file_name: &str) -> Result<(), Error> {
-        let file_info = FileInfo::from(file_name);
+        let mut file_info = FileInfo::from(file_name);
         self.save_to_file(&file_info)?;
+        file_info.load_metadata();
         self.file_info = file_info;
-        self.dirty = false;
+        self.project_settings = editorconfig::settings_for(Path::new(file_name));
+        self.read_only = false;
+        self.lossy = false;
+        self.mark_clean();
         Ok(())
     }
 
     pub fn save(&mut self) -> Result<(), Error> {
+        if self.lossy {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "lossy buffer can only be saved to a new file",
+            ));
+        }
         self.save_to_file(&self.file_info)?;
-        self.dirty = false;
+        self.mark_clean();
         Ok(())
     }
     pub fn is_empty(&self) -> bool {
@@ -161,15 +556,56 @@ impl Buffer {
     pub fn height(&self) -> LineIdx {
         self.lines.len()
     }
-    pub fn insert_char(&mut self, character: char, at: Location) {
+
+    /// Inserts `character` at `at` and returns the location right after the
+    /// grapheme it ended up in.
+    ///
+    /// Inserting at `height()` appends a line, so an empty buffer can be
+    /// typed into.
+    pub fn insert_char(&mut self, character: char, at: Location) -> Location {
         debug_assert!(at.line_idx <= self.height());
         if at.line_idx == self.height() {
-            self.lines.push(Line::from(&character.to_string()));
-            self.dirty = true;
-        } else if let Some(line) = self.lines.get_mut(at.line_idx) {
-            line.insert_char(character, at.grapheme_idx);
-            self.dirty = true;
+            self.lines.push(Line::with_tab_style("", self.tab_style));
+        }
+        let Some(line) = self.lines.get_mut(at.line_idx) else {
+            return at;
+        };
+        let grapheme_idx = line.insert_char(character, at.grapheme_idx);
+        self.mark_dirty();
+        Location {
+            line_idx: at.line_idx,
+            grapheme_idx,
+        }
+    }
+
+    /// Inserts `string` at `at` and returns the location right after it.
+    /// `\n` and `\r\n` both start a new line, which is saved with the
+    /// buffer's line ending; any other `\r` is dropped.
+    pub fn insert_str(&mut self, string: &str, at: Location) -> Location {
+        if at.line_idx == self.height() && !string.is_empty() {
+            self.lines.push(Line::with_tab_style("", self.tab_style));
+        }
+        let mut location = at;
+        for (idx, segment) in string.split('\n').enumerate() {
+            if idx > 0 {
+                self.insert_newline(location);
+                location = Location {
+                    line_idx: location.line_idx.saturating_add(1),
+                    grapheme_idx: 0,
+                };
+            }
+            let segment = segment.replace('\r', "");
+            if let Some(line) = self.lines.get_mut(location.line_idx)
+                && !segment.is_empty()
+            {
+                let old_len = line.grapheme_count();
+                line.insert_str(&segment, location.grapheme_idx);
+                let grapheme_delta = line.grapheme_count().saturating_sub(old_len);
+                location.grapheme_idx = location.grapheme_idx.saturating_add(grapheme_delta);
+                self.mark_dirty();
+            }
         }
+        location
     }
 
     pub fn delete(&mut self, at: Location) {
@@ -180,23 +616,239 @@ impl Buffer {
                 let next_line = self.lines.remove(at.line_idx.saturating_add(1));
                 #[allow(clippy::indexing_slicing)]
                 self.lines[at.line_idx].append(&next_line);
-                self.dirty = true;
+                self.mark_dirty();
             } else if at.grapheme_idx < line.grapheme_count() {
                 #[allow(clippy::indexing_slicing)]
                 self.lines[at.line_idx].delete(at.grapheme_idx);
-                self.dirty = true;
+                self.mark_dirty();
+            }
+        }
+    }
+
+    /// Deletes from `at` to the end of its line. Only meant for locations
+    /// before the end of the line; at the end, `delete` joins the lines.
+    pub fn delete_to_end_of_line(&mut self, at: Location) {
+        if let Some(line) = self.lines.get_mut(at.line_idx)
+            && at.grapheme_idx < line.grapheme_count()
+        {
+            line.split(at.grapheme_idx);
+            self.mark_dirty();
+        }
+    }
+
+    pub fn swap_graphemes(&mut self, at: Location) {
+        if let Some(line) = self.lines.get_mut(at.line_idx)
+            && at.grapheme_idx.saturating_add(1) < line.grapheme_count()
+        {
+            line.swap_graphemes(at.grapheme_idx);
+            self.mark_dirty();
+        }
+    }
+
+    /// Inserts a new line holding `text` before line `at`; at `height()` it
+    /// becomes the last line.
+    pub fn insert_line(&mut self, at: LineIdx, text: &str) {
+        let at = at.min(self.height());
+        self.lines.insert(at, Line::with_tab_style(text, self.tab_style));
+        self.mark_dirty();
+    }
+
+    pub fn replace_range(&mut self, line_idx: LineIdx, range: Range<GraphemeIdx>, text: &str) {
+        if let Some(line) = self.lines.get_mut(line_idx) {
+            line.replace_range(range, text);
+            self.mark_dirty();
+        }
+    }
+
+    /// Removes every empty line that follows another empty line. Returns
+    /// the indices the removed lines had, in ascending order.
+    pub fn squeeze_blank_lines(&mut self) -> Vec<LineIdx> {
+        let mut removed = Vec::new();
+        let mut previous_blank = false;
+        let mut line_idx: LineIdx = 0;
+        self.lines.retain(|line| {
+            let blank = line.is_empty();
+            let keep = !(blank && previous_blank);
+            if !keep {
+                removed.push(line_idx);
             }
+            previous_blank = blank;
+            line_idx = line_idx.saturating_add(1);
+            keep
+        });
+        if !removed.is_empty() {
+            self.mark_dirty();
+        }
+        removed
+    }
+
+    /// Converts the tabs in the indentation, or all of them with `all`, to
+    /// spaces and returns the lines that changed.
+    pub fn tabs_to_spaces(&mut self, all: bool) -> Vec<LineIdx> {
+        self.rewrite_lines(|line| line.tabs_to_spaces(all))
+    }
+
+    /// Converts the indentation to tabs and returns the lines that changed.
+    pub fn spaces_to_tabs(&mut self) -> Vec<LineIdx> {
+        self.rewrite_lines(Line::spaces_to_tabs)
+    }
+
+    /// Drops the whitespace at the end of each line and returns the lines
+    /// that changed.
+    pub fn trim_trailing_whitespace(&mut self) -> Vec<LineIdx> {
+        self.rewrite_lines(Line::trim_trailing_whitespace)
+    }
+
+    fn rewrite_lines(&mut self, rewrite: impl Fn(&mut Line) -> bool) -> Vec<LineIdx> {
+        let changed: Vec<LineIdx> = self
+            .lines
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(idx, line)| rewrite(line).then_some(idx))
+            .collect();
+        if !changed.is_empty() {
+            self.mark_dirty();
+        }
+        changed
+    }
+
+    pub fn swap_lines(&mut self, first: LineIdx, second: LineIdx) {
+        if first < self.height() && second < self.height() && first != second {
+            self.lines.swap(first, second);
+            self.mark_dirty();
         }
     }
 
     pub fn insert_newline(&mut self, at: Location) {
         if at.line_idx == self.height() {
-            self.lines.push(Line::default());
-            self.dirty = true;
-        } else if let Some(line) = self.lines.get_mut(at.line_idx) {
+            self.lines.push(Line::with_tab_style("", self.tab_style));
+        }
+        if let Some(line) = self.lines.get_mut(at.line_idx) {
             let newline = line.split(at.grapheme_idx);
             self.lines.insert(at.line_idx.saturating_add(1), newline);
-            self.dirty = true;
+            self.mark_dirty();
         }
     }
 }
+
+/// The opening and closing bracket for `grapheme`, and whether the partner
+/// lies forward of it.
+fn bracket_pair(grapheme: &str) -> Option<(char, char, bool)> {
+    match grapheme {
+        "(" => Some(('(', ')', true)),
+        "[" => Some(('[', ']', true)),
+        "{" => Some(('{', '}', true)),
+        ")" => Some(('(', ')', false)),
+        "]" => Some(('[', ']', false)),
+        "}" => Some(('{', '}', false)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::LineChange, *};
+
+    use std::{fs, path::PathBuf};
+
+    #[cfg(unix)]
+    #[test]
+    fn save_writes_through_symlink() {
+        let dir = ScratchDir::new("symlink");
+        let target = dir.join("target.txt");
+        let link = dir.join("link.txt");
+        fs::write(&target, "old\n").unwrap();
+        std::os::unix::fs::symlink("target.txt", &link).unwrap();
+
+        let mut buffer = Buffer::load(link.to_str().unwrap()).unwrap();
+        buffer.insert_str("new ", Location::default());
+        buffer.save().unwrap();
+
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), PathBuf::from("target.txt"));
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new old\n");
+    }
+
+    #[test]
+    fn changes_since_baseline_follow_edits_and_saves() {
+        let dir = ScratchDir::new("changes");
+        let path = dir.join("file.txt");
+        fs::write(&path, "a\nb\n").unwrap();
+        let mut buffer = Buffer::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(buffer.changes_since_baseline().count(LineChange::Changed), 0);
+
+        buffer.insert_char('x', Location::default());
+        assert_eq!(buffer.changes_since_baseline().change(0), LineChange::Changed);
+
+        buffer.insert_line(2, "c");
+        assert_eq!(buffer.changes_since_baseline().change(2), LineChange::Added);
+
+        buffer.save().unwrap();
+        let changes = buffer.changes_since_baseline();
+        assert_eq!(changes.count(LineChange::Unchanged), 3);
+    }
+
+    #[test]
+    fn search_backward_crosses_line_boundaries() {
+        let buffer = Buffer::from_text("foo bar\nbaz foo\nqux");
+        let at = |line_idx, grapheme_idx| Location {
+            grapheme_idx,
+            line_idx,
+        };
+        let search = |from| buffer.search_backward("foo", from, false);
+
+        assert_eq!(search(at(1, 5)), Some(at(1, 4)), "match straddling the caret");
+        assert_eq!(search(at(1, 4)), Some(at(0, 0)), "previous line");
+        assert_eq!(search(at(2, 0)), Some(at(1, 4)), "from the start of a line");
+        assert_eq!(search(at(3, 0)), Some(at(1, 4)), "from past the last line");
+        assert_eq!(search(at(0, 0)), Some(at(1, 4)), "wraps to the end");
+    }
+
+    fn written(buffer: &Buffer) -> String {
+        let mut bytes = Vec::new();
+        buffer.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), buffer.byte_count());
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn trailing_newlines_survive_a_round_trip() {
+        for contents in ["", "\n", "\n\n", "a", "a\n", "a\n\n", "a\r\n\r\n"] {
+            assert_eq!(written(&Buffer::from_text(contents)), contents, "{contents:?}");
+        }
+    }
+
+    #[test]
+    fn empty_last_line_keeps_its_line_break() {
+        let mut buffer = Buffer::from_text("a");
+        buffer.insert_newline(Location {
+            grapheme_idx: 1,
+            line_idx: 0,
+        });
+        assert_eq!(written(&buffer), "a\n\n");
+        assert_eq!(Buffer::from_text("a\n\n").height(), buffer.height());
+
+        let mut buffer = Buffer::from_text("a\n\n");
+        buffer.set_final_newline(false);
+        assert_eq!(written(&buffer), "a\n\n");
+    }
+
+    #[test]
+    fn pasted_carriage_returns_do_not_reach_the_lines() {
+        let mut buffer = Buffer::default();
+        let end = buffer.insert_str("a\r\nb\rc\n", Location::default());
+        assert_eq!(buffer.height(), 3);
+        assert_eq!(
+            end,
+            Location {
+                grapheme_idx: 0,
+                line_idx: 2,
+            }
+        );
+        assert_eq!(written(&buffer), "a\nbc\n\n");
+
+        let mut buffer = Buffer::from_text("x\r\n");
+        buffer.insert_str("a\nb", Location::default());
+        assert_eq!(written(&buffer), "a\r\nbx\r\n");
+    }
+}
@@ -1,5 +1,7 @@
 use std::{
+    env,
     fmt::{self, Display},
+    fs::{metadata, symlink_metadata},
     path::{Path, PathBuf},
 };
 
@@ -9,20 +11,72 @@ use super::FileType;
 pub struct FileInfo {
     path: Option<PathBuf>,
     file_type: FileType,
+    is_symlink: bool,
+    is_executable: bool,
+    is_read_only: bool,
+    size: Option<u64>,
 }
 impl FileInfo {
     pub fn from(file_name: &str) -> Self {
         let path_buf = PathBuf::from(file_name);
         let file_type = FileType::from(&path_buf);
-        Self {
+        let mut file_info = Self {
             path: Some(path_buf),
             file_type,
-        }
+            is_symlink: false,
+            is_executable: false,
+            is_read_only: false,
+            size: None,
+        };
+        file_info.load_metadata();
+        file_info
     }
+
+    pub fn load_metadata(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        self.is_symlink = symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink());
+        let target_metadata = metadata(path).ok();
+        self.is_executable = target_metadata
+            .as_ref()
+            .is_some_and(Self::has_exec_permission);
+        self.size = target_metadata.as_ref().map(std::fs::Metadata::len);
+        self.is_read_only = target_metadata.is_some_and(|meta| meta.permissions().readonly());
+    }
+
+    #[cfg(unix)]
+    fn has_exec_permission(meta: &std::fs::Metadata) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+        meta.is_file() && meta.permissions().mode() & 0o111 != 0
+    }
+
+    #[cfg(not(unix))]
+    fn has_exec_permission(_: &std::fs::Metadata) -> bool {
+        false
+    }
+
     pub fn get_path(&self) -> Option<&Path> {
         self.path.as_deref()
     }
 
+    /// The path as given, with the home directory abbreviated to `~`.
+    pub fn get_display_path(&self) -> Option<String> {
+        let path = self.path.as_ref()?;
+        let home = env::var_os("HOME").map(PathBuf::from);
+        let abbreviated = home
+            .filter(|home| !home.as_os_str().is_empty())
+            .and_then(|home| path.strip_prefix(home).ok())
+            .map(|rest| Path::new("~").join(rest));
+        Some(
+            abbreviated
+                .as_deref()
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+
     pub const fn has_path(&self) -> bool {
         self.path.is_some()
     }
@@ -30,6 +84,22 @@ impl FileInfo {
     pub fn get_file_type(&self) -> FileType {
         self.file_type
     }
+
+    pub const fn is_symlink(&self) -> bool {
+        self.is_symlink
+    }
+
+    pub const fn is_executable(&self) -> bool {
+        self.is_executable
+    }
+
+    pub const fn is_read_only(&self) -> bool {
+        self.is_read_only
+    }
+
+    pub const fn get_size(&self) -> Option<u64> {
+        self.size
+    }
 }
 impl Display for FileInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -0,0 +1,44 @@
+use super::{Annotation, AnnotationType, Line, SyntaxHighlighter};
+use crate::prelude::*;
+use std::collections::HashMap;
+
+/// Marks single-grapheme brackets at fixed locations, like the one under
+/// the caret and its partner.
+#[derive(Default)]
+pub struct BracketHighlighter {
+    brackets: Vec<(Location, AnnotationType)>,
+    highlights: HashMap<LineIdx, Vec<Annotation>>,
+}
+
+impl BracketHighlighter {
+    pub fn new(brackets: Vec<(Location, AnnotationType)>) -> Self {
+        Self {
+            brackets,
+            highlights: HashMap::new(),
+        }
+    }
+}
+
+impl SyntaxHighlighter for BracketHighlighter {
+    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(&idx)
+    }
+
+    fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        let result = self
+            .brackets
+            .iter()
+            .filter(|(location, _)| location.line_idx == idx)
+            .filter_map(|&(location, annotation_type)| {
+                let bracket = line.grapheme_at(location.grapheme_idx)?;
+                let start = line.grapheme_idx_to_byte_idx(location.grapheme_idx);
+                Some(Annotation {
+                    annotation_type,
+                    start,
+                    end: start.saturating_add(bracket.len()),
+                })
+            })
+            .collect();
+        self.highlights.insert(idx, result);
+    }
+}
@@ -0,0 +1,48 @@
+use super::{super::LineDiff, super::LineChange, Annotation, AnnotationType, Line, SyntaxHighlighter};
+use crate::prelude::*;
+use std::collections::HashMap;
+
+/// Marks changed and added lines as a whole, and the first grapheme of a
+/// line that removed lines used to precede.
+pub struct DiffHighlighter<'a> {
+    diff: &'a LineDiff,
+    highlights: HashMap<LineIdx, Vec<Annotation>>,
+}
+
+impl<'a> DiffHighlighter<'a> {
+    pub fn new(diff: &'a LineDiff) -> Self {
+        Self {
+            diff,
+            highlights: HashMap::new(),
+        }
+    }
+}
+
+impl SyntaxHighlighter for DiffHighlighter<'_> {
+    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(&idx)
+    }
+
+    fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        let mut result = Vec::new();
+        let annotation_type = match self.diff.change(idx) {
+            LineChange::Unchanged => None,
+            LineChange::Changed => Some(AnnotationType::DiffChanged),
+            LineChange::Added => Some(AnnotationType::DiffAdded),
+        };
+        if let Some(annotation_type) = annotation_type {
+            result.push(Annotation {
+                annotation_type,
+                start: 0,
+                end: line.len(),
+            });
+        } else if self.diff.removed_before(idx) > 0 {
+            result.push(Annotation {
+                annotation_type: AnnotationType::DiffRemoved,
+                start: 0,
+                end: line.byte_idx_after(0, 1),
+            });
+        }
+        self.highlights.insert(idx, result);
+    }
+}
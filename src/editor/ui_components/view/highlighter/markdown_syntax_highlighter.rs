@@ -0,0 +1,93 @@
+use super::{Annotation, AnnotationType, Line, SyntaxHighlighter};
+use crate::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct MarkdownSyntaxHighlighter {
+    highlights: HashMap<LineIdx, Vec<Annotation>>,
+    in_code_fence: bool,
+}
+
+impl MarkdownSyntaxHighlighter {
+    fn is_code_fence(line: &Line) -> bool {
+        let trimmed = line.trim_start();
+        trimmed.starts_with("```") || trimmed.starts_with("~~~")
+    }
+
+    fn is_heading(line: &Line) -> bool {
+        let trimmed = line.trim_start();
+        let level = trimmed.bytes().take_while(|&byte| byte == b'#').count();
+        (1..=6).contains(&level)
+            && trimmed
+                .get(level..)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+    }
+
+    fn whole_line(line: &Line, annotation_type: AnnotationType) -> Annotation {
+        Annotation {
+            annotation_type,
+            start: 0,
+            end: line.len(),
+        }
+    }
+
+    fn find_closing(bytes: &[u8], from: ByteIdx, delimiter: &[u8]) -> Option<ByteIdx> {
+        bytes
+            .get(from..)?
+            .windows(delimiter.len())
+            .position(|window| window == delimiter)
+            .map(|position| position.saturating_add(from))
+    }
+
+    #[allow(clippy::arithmetic_side_effects)]
+    fn highlight_inline(line: &Line, result: &mut Vec<Annotation>) {
+        let bytes = line.as_bytes();
+        let mut idx = 0;
+        while idx < bytes.len() {
+            let (delimiter, annotation_type): (&[u8], _) = if bytes[idx..].starts_with(b"`") {
+                (b"`", AnnotationType::Code)
+            } else if bytes[idx..].starts_with(b"**") {
+                (b"**", AnnotationType::Bold)
+            } else if bytes[idx..].starts_with(b"*") {
+                (b"*", AnnotationType::Italic)
+            } else {
+                idx += 1;
+                continue;
+            };
+            let content_start = idx + delimiter.len();
+            match Self::find_closing(bytes, content_start, delimiter) {
+                Some(closing) if closing > content_start => {
+                    let end = closing + delimiter.len();
+                    result.push(Annotation {
+                        annotation_type,
+                        start: idx,
+                        end,
+                    });
+                    idx = end;
+                },
+                _ => idx = content_start,
+            }
+        }
+    }
+}
+
+impl SyntaxHighlighter for MarkdownSyntaxHighlighter {
+    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(&idx)
+    }
+
+    fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        let mut result = Vec::new();
+        if Self::is_code_fence(line) {
+            self.in_code_fence = !self.in_code_fence;
+            result.push(Self::whole_line(line, AnnotationType::Code));
+        } else if self.in_code_fence {
+            result.push(Self::whole_line(line, AnnotationType::Code));
+        } else if Self::is_heading(line) {
+            result.push(Self::whole_line(line, AnnotationType::Heading));
+        } else {
+            Self::highlight_inline(line, &mut result);
+        }
+        self.highlights.insert(idx, result);
+    }
+}
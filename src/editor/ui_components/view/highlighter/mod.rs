@@ -1,57 +1,135 @@
 mod syntax_highlighter;
-use super::super::super::{Annotation, AnnotationType, FileType, Line};
+use super::{
+    super::super::{Annotation, AnnotationType, FileType, Line},
+    LineDiff,
+};
 use crate::prelude::*;
 use syntax_highlighter::SyntaxHighlighter;
 
+mod bracket_highlighter;
+use bracket_highlighter::BracketHighlighter;
+mod diff_highlighter;
+use diff_highlighter::DiffHighlighter;
+mod markdown_syntax_highlighter;
+use markdown_syntax_highlighter::MarkdownSyntaxHighlighter;
 mod rust_syntax_highlighter;
 use rust_syntax_highlighter::RustSyntaxHighlighter;
 mod search_result_highlighter;
 use search_result_highlighter::SearchResultHighlighter;
+mod whitespace_highlighter;
+use whitespace_highlighter::WhitespaceHighlighter;
+mod word_occurrence_highlighter;
+use word_occurrence_highlighter::WordOccurrenceHighlighter;
 
 fn create_syntax_highlighter(file_type: FileType) -> Option<Box<dyn SyntaxHighlighter>> {
     match file_type {
         FileType::Rust => Some(Box::<RustSyntaxHighlighter>::default()),
-        _ => None,
+        FileType::Markdown => Some(Box::<MarkdownSyntaxHighlighter>::default()),
+        FileType::PlainText => None,
     }
 }
 #[derive(Default)]
+#[allow(clippy::struct_field_names)]
 pub struct Highlighter<'a> {
     syntax_highlighter: Option<Box<dyn SyntaxHighlighter>>,
     search_result_highlighter: Option<SearchResultHighlighter<'a>>,
+    whitespace_highlighter: Option<WhitespaceHighlighter>,
+    bracket_highlighter: BracketHighlighter,
+    word_occurrence_highlighter: Option<WordOccurrenceHighlighter<'a>>,
+    diff_highlighter: Option<DiffHighlighter<'a>>,
 }
 
 impl<'a> Highlighter<'a> {
     pub fn new(
         matched_word: Option<&'a str>,
+        ignore_case: bool,
         selected_match: Option<Location>,
         file_type: FileType,
     ) -> Self {
-        let search_result_highlighter = matched_word
-            .map(|matched_word| SearchResultHighlighter::new(matched_word, selected_match));
+        let search_result_highlighter = matched_word.map(|matched_word| {
+            SearchResultHighlighter::new(matched_word, ignore_case, selected_match)
+        });
         Self {
             syntax_highlighter: create_syntax_highlighter(file_type),
             search_result_highlighter,
+            whitespace_highlighter: None,
+            bracket_highlighter: BracketHighlighter::default(),
+            word_occurrence_highlighter: None,
+            diff_highlighter: None,
         }
     }
 
+    /// Annotates the given single-grapheme locations, e.g. a bracket and its
+    /// partner. Must be called before the lines are highlighted.
+    pub fn set_brackets(&mut self, brackets: Vec<(Location, AnnotationType)>) {
+        self.bracket_highlighter = BracketHighlighter::new(brackets);
+    }
+
+    /// Annotates the other occurrences of `word`, skipping the one starting
+    /// at `skip`. Must be called before the lines are highlighted.
+    pub fn set_word_occurrences(&mut self, word: &'a str, skip: Location) {
+        self.word_occurrence_highlighter = Some(WordOccurrenceHighlighter::new(word, skip));
+    }
+
+    /// Marks whitespace at the end of lines. Must be called before the
+    /// lines are highlighted.
+    pub fn set_trailing_whitespace(&mut self) {
+        self.whitespace_highlighter = Some(WhitespaceHighlighter::default());
+    }
+
+    /// Marks the lines that differ according to `diff`. Must be called
+    /// before the lines are highlighted.
+    pub fn set_diff(&mut self, diff: &'a LineDiff) {
+        self.diff_highlighter = Some(DiffHighlighter::new(diff));
+    }
+
     pub fn get_annotations(&self, idx: LineIdx) -> Vec<Annotation> {
         let mut result = Vec::new();
-        if let Some(syntax_highlighter) = &self.syntax_highlighter {
-            if let Some(annotations) = syntax_highlighter.get_annotations(idx) {
-                result.extend(annotations.iter().cloned());
-            }
+        if let Some(diff_highlighter) = &self.diff_highlighter
+            && let Some(annotations) = diff_highlighter.get_annotations(idx)
+        {
+            result.extend(annotations.iter().copied());
         }
-        if let Some(search_result_highlighter) = &self.search_result_highlighter {
-            if let Some(annotations) = search_result_highlighter.get_annotations(idx) {
-                result.extend(annotations.iter().cloned());
-            }
+        if let Some(syntax_highlighter) = &self.syntax_highlighter
+            && let Some(annotations) = syntax_highlighter.get_annotations(idx)
+        {
+            result.extend(annotations.iter().copied());
+        }
+        if let Some(whitespace_highlighter) = &self.whitespace_highlighter
+            && let Some(annotations) = whitespace_highlighter.get_annotations(idx)
+        {
+            result.extend(annotations.iter().copied());
+        }
+        if let Some(word_occurrence_highlighter) = &self.word_occurrence_highlighter
+            && let Some(annotations) = word_occurrence_highlighter.get_annotations(idx)
+        {
+            result.extend(annotations.iter().copied());
+        }
+        if let Some(annotations) = self.bracket_highlighter.get_annotations(idx) {
+            result.extend(annotations.iter().copied());
+        }
+        if let Some(search_result_highlighter) = &self.search_result_highlighter
+            && let Some(annotations) = search_result_highlighter.get_annotations(idx)
+        {
+            result.extend(annotations.iter().copied());
         }
         result
     }
+
     pub fn highlight(&mut self, idx: LineIdx, line: &Line) {
         if let Some(syntax_highlighter) = &mut self.syntax_highlighter {
             syntax_highlighter.highlight(idx, line);
         }
+        if let Some(whitespace_highlighter) = &mut self.whitespace_highlighter {
+            whitespace_highlighter.highlight(idx, line);
+        }
+        if let Some(diff_highlighter) = &mut self.diff_highlighter {
+            diff_highlighter.highlight(idx, line);
+        }
+        if let Some(word_occurrence_highlighter) = &mut self.word_occurrence_highlighter {
+            word_occurrence_highlighter.highlight(idx, line);
+        }
+        self.bracket_highlighter.highlight(idx, line);
         if let Some(search_result_highlighter) = &mut self.search_result_highlighter {
             search_result_highlighter.highlight(idx, line);
         }
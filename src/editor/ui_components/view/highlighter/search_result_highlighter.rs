@@ -1,18 +1,21 @@
 use super::{Annotation, AnnotationType, Line, SyntaxHighlighter};
 use crate::prelude::*;
 use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Default)]
 pub struct SearchResultHighlighter<'a> {
     matched_word: &'a str,
+    ignore_case: bool,
     selected_match: Option<Location>,
     highlights: HashMap<LineIdx, Vec<Annotation>>,
 }
 
 impl<'a> SearchResultHighlighter<'a> {
-    pub fn new(matched_word: &'a str, selected_match: Option<Location>) -> Self {
+    pub fn new(matched_word: &'a str, ignore_case: bool, selected_match: Option<Location>) -> Self {
         Self {
             matched_word,
+            ignore_case,
             selected_match,
             highlights: HashMap::new(),
         }
@@ -21,13 +24,14 @@ impl<'a> SearchResultHighlighter<'a> {
         if self.matched_word.is_empty() {
             return;
         }
-        line.find_all(self.matched_word, 0..line.len())
+        let grapheme_count = self.matched_word.graphemes(true).count();
+        line.find_all(self.matched_word, 0..line.len(), self.ignore_case)
             .iter()
-            .for_each(|(start, _)| {
+            .for_each(|&(start, grapheme_idx)| {
                 result.push(Annotation {
                     annotation_type: AnnotationType::Match,
-                    start: *start,
-                    end: start.saturating_add(self.matched_word.len()),
+                    start,
+                    end: line.byte_idx_after(grapheme_idx, grapheme_count),
                 });
             });
     }
@@ -40,14 +44,17 @@ impl<'a> SearchResultHighlighter<'a> {
             let annotation = Annotation {
                 annotation_type: AnnotationType::SelectedMatch,
                 start,
-                end: start.saturating_add(self.matched_word.len()),
+                end: line.byte_idx_after(
+                    selected_match.grapheme_idx,
+                    self.matched_word.graphemes(true).count(),
+                ),
             };
             result.push(annotation);
         }
     }
 }
 
-impl<'a> SyntaxHighlighter for SearchResultHighlighter<'a> {
+impl SyntaxHighlighter for SearchResultHighlighter<'_> {
     fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
         self.highlights.get(&idx)
     }
@@ -55,10 +62,10 @@ impl<'a> SyntaxHighlighter for SearchResultHighlighter<'a> {
     fn highlight(&mut self, idx: LineIdx, line: &Line) {
         let mut result = Vec::new();
         self.highlight_matched_words(line, &mut result);
-        if let Some(selected_match) = self.selected_match {
-            if selected_match.line_idx == idx {
-                self.highlight_selected_match(line, &mut result);
-            }
+        if let Some(selected_match) = self.selected_match
+            && selected_match.line_idx == idx
+        {
+            self.highlight_selected_match(line, &mut result);
         }
         self.highlights.insert(idx, result);
     }
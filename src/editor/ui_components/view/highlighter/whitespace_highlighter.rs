@@ -0,0 +1,27 @@
+use super::{Annotation, AnnotationType, Line, SyntaxHighlighter};
+use crate::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct WhitespaceHighlighter {
+    highlights: HashMap<LineIdx, Vec<Annotation>>,
+}
+
+impl SyntaxHighlighter for WhitespaceHighlighter {
+    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(&idx)
+    }
+
+    fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        let mut result = Vec::new();
+        let content_end = line.trim_end().len();
+        if content_end < line.len() {
+            result.push(Annotation {
+                annotation_type: AnnotationType::TrailingWhitespace,
+                start: content_end,
+                end: line.len(),
+            });
+        }
+        self.highlights.insert(idx, result);
+    }
+}
@@ -0,0 +1,49 @@
+use super::{Annotation, AnnotationType, Line, SyntaxHighlighter};
+use crate::prelude::*;
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Marks the whole-word occurrences of a word, except the one at `skip`.
+#[derive(Default)]
+pub struct WordOccurrenceHighlighter<'a> {
+    word: &'a str,
+    skip: Location,
+    highlights: HashMap<LineIdx, Vec<Annotation>>,
+}
+
+impl<'a> WordOccurrenceHighlighter<'a> {
+    pub fn new(word: &'a str, skip: Location) -> Self {
+        Self {
+            word,
+            skip,
+            highlights: HashMap::new(),
+        }
+    }
+}
+
+impl SyntaxHighlighter for WordOccurrenceHighlighter<'_> {
+    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(&idx)
+    }
+
+    fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        let word_len = self.word.graphemes(true).count();
+        let result = line
+            .find_whole_words(self.word)
+            .into_iter()
+            .filter(|&(_, grapheme_idx)| {
+                self.skip
+                    != Location {
+                        grapheme_idx,
+                        line_idx: idx,
+                    }
+            })
+            .map(|(start, grapheme_idx)| Annotation {
+                annotation_type: AnnotationType::WordOccurrence,
+                start,
+                end: line.byte_idx_after(grapheme_idx, word_len),
+            })
+            .collect();
+        self.highlights.insert(idx, result);
+    }
+}
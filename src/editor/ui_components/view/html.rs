@@ -0,0 +1,70 @@
+use super::AnnotationType;
+
+/// The colors follow the terminal attributes of the same annotation types.
+const STYLE: &str = "\
+body { background: #1e1e1e; color: #d4d4d4; }
+.match { color: #ffffff; background: #d3d3d3; }
+.selected-match { color: #ffffff; background: #ffff99; }
+.digit { color: #ff6347; }
+.heading { color: #6495ed; }
+.bold { color: #ffa500; }
+.italic { color: #da70d6; }
+.code { color: #90ee90; }
+.error { color: #dc143c; }
+.warning { color: #ffd700; }
+.trailing-whitespace { background: #b22222; }
+.matching-bracket { background: #4682b4; }
+.unmatched-bracket { color: #ffffff; background: #dc143c; }
+.word-occurrence { background: #505050; }
+.diff-added { background: #005f00; }
+.diff-changed { background: #004678; }
+.diff-removed { background: #960000; }
+.clip { color: #000000; background: #b4b4b4; }
+";
+
+pub const fn css_class(annotation_type: AnnotationType) -> &'static str {
+    match annotation_type {
+        AnnotationType::Match => "match",
+        AnnotationType::SelectedMatch => "selected-match",
+        AnnotationType::Digit => "digit",
+        AnnotationType::Heading => "heading",
+        AnnotationType::Bold => "bold",
+        AnnotationType::Italic => "italic",
+        AnnotationType::Code => "code",
+        AnnotationType::Error => "error",
+        AnnotationType::Warning => "warning",
+        AnnotationType::TrailingWhitespace => "trailing-whitespace",
+        AnnotationType::MatchingBracket => "matching-bracket",
+        AnnotationType::UnmatchedBracket => "unmatched-bracket",
+        AnnotationType::WordOccurrence => "word-occurrence",
+        AnnotationType::DiffAdded => "diff-added",
+        AnnotationType::DiffChanged => "diff-changed",
+        AnnotationType::DiffRemoved => "diff-removed",
+        AnnotationType::Clip => "clip",
+    }
+}
+
+pub fn push_escaped(html: &mut String, text: &str) {
+    for character in text.chars() {
+        match character {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            _ => html.push(character),
+        }
+    }
+}
+
+/// Wraps already escaped `body` in a standalone page showing it as
+/// preformatted text.
+pub fn document(title: &str, body: &str) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>");
+    push_escaped(&mut html, title);
+    html.push_str("</title>\n<style>\n");
+    html.push_str(STYLE);
+    html.push_str("</style>\n</head>\n<body>\n<pre>");
+    html.push_str(body);
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
@@ -0,0 +1,53 @@
+use crate::prelude::*;
+
+/// The oldest jumps are dropped beyond this many.
+const MAX_JUMPS: usize = 100;
+
+/// Where the caret was before each jump, walked like browser history.
+/// Entries can go stale through edits, so they are clamped when used.
+#[derive(Default)]
+pub struct JumpList {
+    locations: Vec<Location>,
+    /// The entry the caret was last taken to; `locations.len()` while no
+    /// jump back is in progress.
+    current: usize,
+}
+
+impl JumpList {
+    /// Records `location` as left by a jump. Entries ahead of the current
+    /// one are dropped, as in a browser.
+    pub fn push(&mut self, location: Location) {
+        self.locations.truncate(self.current);
+        if self.locations.last() != Some(&location) {
+            self.locations.push(location);
+        }
+        if self.locations.len() > MAX_JUMPS {
+            self.locations.remove(0);
+        }
+        self.current = self.locations.len();
+    }
+
+    /// The location before the current one. Going back from `caret` for
+    /// the first time records it, so `forward` can return there.
+    pub fn back(&mut self, caret: Location) -> Option<Location> {
+        if self.current == self.locations.len() {
+            if self.locations.last() != Some(&caret) {
+                self.locations.push(caret);
+            }
+            self.current = self.locations.len().saturating_sub(1);
+        }
+        self.current = self.current.checked_sub(1)?;
+        self.locations.get(self.current).copied()
+    }
+
+    pub fn forward(&mut self) -> Option<Location> {
+        let next = self.current.saturating_add(1);
+        let location = self.locations.get(next).copied()?;
+        self.current = next;
+        Some(location)
+    }
+
+    pub fn locations_mut(&mut self) -> impl Iterator<Item = &mut Location> {
+        self.locations.iter_mut()
+    }
+}
@@ -0,0 +1,200 @@
+use crate::prelude::*;
+
+/// Beyond this many table cells the differing middle part is reported as
+/// changed wholesale instead of aligned line by line.
+const MAX_TABLE_CELLS: usize = 4_000_000;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum LineChange {
+    #[default]
+    Unchanged,
+    Changed,
+    Added,
+}
+
+/// How the lines of a new text differ from an old one.
+#[derive(Default)]
+pub struct LineDiff {
+    /// One entry per new line.
+    pub changes: Vec<LineChange>,
+    /// How many old lines were removed right before each new line; the
+    /// extra last entry counts removals at the end.
+    pub removed: Vec<usize>,
+}
+
+impl LineDiff {
+    pub fn change(&self, idx: LineIdx) -> LineChange {
+        self.changes.get(idx).copied().unwrap_or_default()
+    }
+
+    pub fn removed_before(&self, idx: LineIdx) -> usize {
+        self.removed.get(idx).copied().unwrap_or(0)
+    }
+
+    pub fn count(&self, change: LineChange) -> usize {
+        self.changes.iter().filter(|&&line| line == change).count()
+    }
+
+    pub fn removed_count(&self) -> usize {
+        self.removed.iter().sum()
+    }
+
+    /// Records a run of `removed` old lines replaced by `added` new lines
+    /// ending right before `new_idx`: paired lines count as changed.
+    fn push_run(&mut self, new_idx: LineIdx, removed: usize, added: usize) {
+        let changed = removed.min(added);
+        let start = new_idx.saturating_sub(added);
+        for idx in start..new_idx {
+            if let Some(change) = self.changes.get_mut(idx) {
+                *change = if idx < start.saturating_add(changed) {
+                    LineChange::Changed
+                } else {
+                    LineChange::Added
+                };
+            }
+        }
+        if let Some(count) = self.removed.get_mut(new_idx) {
+            *count = count.saturating_add(removed.saturating_sub(changed));
+        }
+    }
+}
+
+/// Aligns the lines along their longest common subsequence, after
+/// skipping the common prefix and suffix.
+pub fn diff_lines<T: PartialEq>(old: &[T], new: &[T]) -> LineDiff {
+    let mut diff = LineDiff {
+        changes: vec![LineChange::Unchanged; new.len()],
+        removed: vec![0; new.len().saturating_add(1)],
+    };
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old_line, new_line)| old_line == new_line)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old_line, new_line)| old_line == new_line)
+        .count();
+    let old = &old[prefix..old.len().saturating_sub(suffix)];
+    let new_end = new.len().saturating_sub(suffix);
+    let new = &new[prefix..new_end];
+
+    let width = new.len().saturating_add(1);
+    let cells = old.len().saturating_add(1).saturating_mul(width);
+    if cells > MAX_TABLE_CELLS {
+        diff.push_run(new_end, old.len(), new.len());
+        return diff;
+    }
+    // `table[i * width + j]` is the length of the longest common
+    // subsequence of `old[i..]` and `new[j..]`.
+    let mut table = vec![0_usize; cells];
+    let cell = |i: usize, j: usize| i.saturating_mul(width).saturating_add(j);
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            let value = if old.get(i) == new.get(j) {
+                table
+                    .get(cell(i.saturating_add(1), j.saturating_add(1)))
+                    .map_or(0, |value| value.saturating_add(1))
+            } else {
+                let below = table.get(cell(i.saturating_add(1), j)).copied();
+                let right = table.get(cell(i, j.saturating_add(1))).copied();
+                below.max(right).unwrap_or(0)
+            };
+            if let Some(slot) = table.get_mut(cell(i, j)) {
+                *slot = value;
+            }
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let (mut removed, mut added) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old.get(i) == new.get(j) {
+            diff.push_run(prefix.saturating_add(j), removed, added);
+            (removed, added) = (0, 0);
+            i = i.saturating_add(1);
+            j = j.saturating_add(1);
+        } else if j >= new.len()
+            || (i < old.len()
+                && table.get(cell(i.saturating_add(1), j))
+                    >= table.get(cell(i, j.saturating_add(1))))
+        {
+            removed = removed.saturating_add(1);
+            i = i.saturating_add(1);
+        } else {
+            added = added.saturating_add(1);
+            j = j.saturating_add(1);
+        }
+    }
+    diff.push_run(new_end, removed, added);
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use LineChange::{Added, Changed, Unchanged};
+
+    #[test]
+    fn identical_lines_are_unchanged() {
+        let diff = diff_lines(&["a", "b", "c"], &["a", "b", "c"]);
+        assert_eq!(diff.changes, [Unchanged; 3]);
+        assert_eq!(diff.removed, [0; 4]);
+    }
+
+    #[test]
+    fn inserted_lines_are_added() {
+        let diff = diff_lines(&["a", "c"], &["a", "b", "b", "c"]);
+        assert_eq!(diff.changes, [Unchanged, Added, Added, Unchanged]);
+        assert_eq!(diff.removed_count(), 0);
+    }
+
+    #[test]
+    fn deleted_lines_are_counted_before_the_next_line() {
+        let diff = diff_lines(&["a", "b", "c", "d"], &["a", "d"]);
+        assert_eq!(diff.changes, [Unchanged, Unchanged]);
+        assert_eq!(diff.removed, [0, 2, 0]);
+    }
+
+    #[test]
+    fn replaced_lines_in_the_middle_are_changed() {
+        let diff = diff_lines(&["a", "b", "c", "d"], &["a", "x", "y", "z", "d"]);
+        assert_eq!(diff.changes, [Unchanged, Changed, Changed, Added, Unchanged]);
+        assert_eq!(diff.removed_count(), 0);
+
+        let diff = diff_lines(&["a", "b", "c", "x", "d"], &["a", "y", "x", "d"]);
+        assert_eq!(diff.changes, [Unchanged, Changed, Unchanged, Unchanged]);
+        assert_eq!(diff.removed, [0, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn lines_removed_at_the_end_use_the_extra_slot() {
+        let diff = diff_lines(&["a", "b", "c"], &["a"]);
+        assert_eq!(diff.changes, [Unchanged]);
+        assert_eq!(diff.removed, [0, 2]);
+        assert_eq!(diff.removed_before(1), 2);
+
+        let diff = diff_lines(&["a"], &[]);
+        assert_eq!(diff.removed, [1]);
+    }
+
+    #[test]
+    fn large_differences_are_reported_wholesale() {
+        let old: Vec<usize> = (0..2_100).collect();
+        let mut new: Vec<usize> = (10_000..12_000).collect();
+        new.insert(0, 0);
+        new.push(2_099);
+        assert!(old.len().saturating_mul(new.len()) > MAX_TABLE_CELLS);
+
+        let diff = diff_lines(&old, &new);
+        assert_eq!(diff.change(0), Unchanged);
+        assert_eq!(diff.count(Changed), 2_000);
+        assert_eq!(diff.count(Added), 0);
+        assert_eq!(diff.change(2_001), Unchanged);
+        assert_eq!(diff.removed_before(2_001), 98);
+        assert_eq!(diff.removed_count(), 98);
+    }
+}
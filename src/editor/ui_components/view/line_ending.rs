@@ -0,0 +1,42 @@
+use std::fmt::{self, Display};
+
+#[derive(Default, Eq, PartialEq, Clone, Copy, Debug)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    // The first line break decides; content without one uses the default.
+    pub fn detect(contents: &str) -> Self {
+        match contents.find('\n') {
+            Some(idx) if contents[..idx].ends_with('\r') => Self::CrLf,
+            _ => Self::Lf,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Lf => "LF",
+            Self::CrLf => "CRLF",
+        }
+    }
+
+    pub const fn len(self) -> usize {
+        match self {
+            Self::Lf => 1,
+            Self::CrLf => 2,
+        }
+    }
+}
+
+impl Display for LineEnding {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let line_ending = match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        };
+        formatter.write_str(line_ending)
+    }
+}
@@ -2,22 +2,57 @@ use crate::prelude::*;
 
 use super::{
     super::{
-        DocumentStatus, FileType, Line, Terminal,
-        command::{Edit, Move},
+        AnnotatedString, Annotation, AnnotationType, ConfigFile, DocumentStatus, EditorConfig, FileSummary, FileType, Line,
+        Renderer, TabStyle,
+        command::{CaseChange, Edit, Move},
     },
     ui_component::UIComponent,
 };
+mod bracket_match;
 mod buffer;
 mod file_info;
 mod highlighter;
+mod html;
+mod jump_list;
+mod line_diff;
+mod line_ending;
+mod search_case;
 mod search_direction;
 mod search_info;
+mod search_scroll;
+mod stashed_buffer;
+mod whitespace_issues;
+use bracket_match::BracketMatch;
 use buffer::Buffer;
 use file_info::FileInfo;
 use highlighter::Highlighter;
+use jump_list::JumpList;
+use line_diff::{LineChange, LineDiff, diff_lines};
+pub use line_ending::LineEnding;
+pub use search_case::SearchCase;
 use search_direction::SearchDirection;
 use search_info::SearchInfo;
-use std::{cmp::min, io::Error, usize};
+pub use search_scroll::SearchScroll;
+use stashed_buffer::StashedBuffer;
+use whitespace_issues::WhitespaceIssues;
+use std::{
+    cmp::min,
+    collections::HashMap,
+    fs,
+    io::{Error, Write},
+    mem,
+    ops::Range,
+    path::Path,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+const LAST_POSITION_MARK: char = '\'';
+/// Counting stops here, so a query matching everywhere in a big file
+/// doesn't slow down typing.
+const MAX_COUNTED_MATCHES: usize = 9999;
+/// A sign and a space.
+const GUTTER_WIDTH: usize = 2;
+
 #[derive(Default)]
 pub struct View {
     buffer: Buffer,
@@ -26,15 +61,91 @@ pub struct View {
     text_location: Location,
     scroll_offset: Position,
     search_info: Option<SearchInfo>,
+    marks: HashMap<char, Location>,
+    jump_list: JumpList,
+    recent_files: Vec<String>,
+    config_file: ConfigFile,
+    config: EditorConfig,
+    stashed: Option<StashedBuffer>,
+    /// The caret location after the last edit. Word occurrences are not
+    /// marked until the caret leaves it, so typing doesn't flicker them.
+    edited_at: Option<Location>,
+    /// The differences to the file on disk while they are shown; any edit
+    /// drops them, as they would go stale.
+    disk_diff: Option<LineDiff>,
 }
 impl View {
     pub fn get_status(&self) -> DocumentStatus {
         DocumentStatus {
             total_lines: self.buffer.height(),
             current_line_idx: self.text_location.line_idx,
-            file_name: format!("{}", self.buffer.get_file_info()),
+            current_grapheme_idx: self.text_location.grapheme_idx,
+            file_name: if self.is_showing_help() {
+                String::from("[Help]")
+            } else {
+                format!("{}", self.buffer.get_file_info())
+            },
             is_modified: self.buffer.is_dirty(),
             file_type: self.buffer.get_file_info().get_file_type(),
+            is_symlink: self.buffer.get_file_info().is_symlink(),
+            is_executable: self.buffer.get_file_info().is_executable(),
+            is_read_only: self.buffer.is_read_only()
+                || self.buffer.get_file_info().is_read_only(),
+            is_lossy: self.buffer.is_lossy(),
+        }
+    }
+
+    /// Shows `text` in a read-only buffer until `close_help`, which brings
+    /// back the current buffer exactly as it was.
+    pub fn show_help(&mut self, text: &str) {
+        if self.is_showing_help() {
+            return;
+        }
+        let mut help = Buffer::from_text(text);
+        help.set_read_only(true);
+        self.stashed = Some(StashedBuffer {
+            buffer: mem::replace(&mut self.buffer, help),
+            text_location: mem::take(&mut self.text_location),
+            scroll_offset: mem::take(&mut self.scroll_offset),
+            marks: mem::take(&mut self.marks),
+            jump_list: mem::take(&mut self.jump_list),
+        });
+        self.set_needs_redraw(true);
+    }
+
+    pub fn close_help(&mut self) {
+        if let Some(stashed) = self.stashed.take() {
+            self.buffer = stashed.buffer;
+            self.text_location = stashed.text_location;
+            self.scroll_offset = stashed.scroll_offset;
+            self.marks = stashed.marks;
+            self.jump_list = stashed.jump_list;
+            self.set_needs_redraw(true);
+        }
+    }
+
+    pub const fn config(&self) -> &EditorConfig {
+        &self.config
+    }
+
+    pub const fn is_showing_help(&self) -> bool {
+        self.stashed.is_some()
+    }
+
+    pub fn get_file_summary(&self) -> FileSummary {
+        let file_info = self.buffer.get_file_info();
+        let in_memory_size = u64::try_from(self.buffer.byte_count()).unwrap_or(u64::MAX);
+        let size = if self.buffer.is_dirty() {
+            in_memory_size
+        } else {
+            file_info.get_size().unwrap_or(in_memory_size)
+        };
+        FileSummary {
+            path: file_info.get_display_path(),
+            size,
+            total_lines: self.buffer.height(),
+            line_ending: self.buffer.get_line_ending().name(),
+            file_type: file_info.get_file_type(),
         }
     }
 
@@ -42,6 +153,42 @@ impl View {
         self.buffer.is_file_loaded()
     }
 
+    pub fn set_config_file(&mut self, config_file: ConfigFile) {
+        self.config_file = config_file;
+        self.refresh_config();
+    }
+
+    // The file type can change on load and on save-as.
+    fn refresh_config(&mut self) {
+        let file_type = self.buffer.get_file_info().get_file_type();
+        self.config = self
+            .config_file
+            .effective(file_type, self.buffer.project_settings());
+        self.buffer.set_tab_style(TabStyle {
+            width: self.config.tab_width,
+            visible: self.config.show_tabs,
+        });
+        if let Some(line_ending) = self.config.line_ending {
+            self.buffer.set_line_ending(line_ending);
+        }
+        if let Some(final_newline) = self.config.insert_final_newline {
+            self.buffer.set_final_newline(final_newline);
+        }
+    }
+
+    pub fn set_recent_files(&mut self, recent_files: &[String]) {
+        self.recent_files = recent_files.to_vec();
+        self.set_needs_redraw(true);
+    }
+
+    pub fn is_showing_welcome(&self) -> bool {
+        self.buffer.is_empty() && !self.buffer.is_file_loaded() && !self.buffer.is_dirty()
+    }
+
+    pub fn analyze_whitespace(&self) -> WhitespaceIssues {
+        self.buffer.analyze_whitespace()
+    }
+
     pub fn enter_search(&mut self) {
         self.search_info = Some(SearchInfo {
             prev_location: self.text_location,
@@ -51,7 +198,16 @@ impl View {
         });
     }
 
+    /// Leaves the caret at the match; where the search started goes on the
+    /// jump list, so a jump back returns there.
     pub fn exit_search(&mut self) {
+        if let Some(search_info) = &self.search_info {
+            self.marks
+                .insert(LAST_POSITION_MARK, search_info.prev_location);
+            if search_info.prev_location != self.text_location {
+                self.jump_list.push(search_info.prev_location);
+            }
+        }
         self.search_info = None;
         self.set_needs_redraw(true);
     }
@@ -82,7 +238,32 @@ impl View {
         );
         query
     }
-    fn is_search_found(&self) -> bool {
+    /// Where the current match is among all matches, like `(3/12)`, or
+    /// `(0/0)` without one. Empty while there is no query.
+    pub fn search_match_summary(&self) -> String {
+        let Some(query) = self
+            .search_info
+            .as_ref()
+            .and_then(|search_info| search_info.query.as_ref())
+            .filter(|query| !query.is_empty())
+        else {
+            return String::new();
+        };
+        let ignore_case = self.config.search_case.ignores_case(query);
+        let (before, total, capped) =
+            self.buffer
+                .count_matches(query, self.text_location, ignore_case, MAX_COUNTED_MATCHES);
+        let more = if capped { "+" } else { "" };
+        if !self.is_search_found() {
+            format!("(0/{total}{more})")
+        } else if before < total {
+            format!("({}/{total}{more})", before.saturating_add(1))
+        } else {
+            format!("(?/{total}{more})")
+        }
+    }
+
+    pub fn is_search_found(&self) -> bool {
         self.search_info
             .as_ref()
             .map_or_else(|| false, |search_info| search_info.found)
@@ -94,16 +275,21 @@ impl View {
     }
     fn search_in_direction(&mut self, from: Location, direction: SearchDirection) {
         if let Some(location) = self.get_search_query().and_then(|query| {
+            let ignore_case = self.config.search_case.ignores_case(query);
             if query.is_empty() {
                 None
             } else if direction == SearchDirection::Forward {
-                self.buffer.search_forward(query, from)
+                self.buffer.search_forward(query, from, ignore_case)
             } else {
-                self.buffer.search_backward(query, from)
+                self.buffer.search_backward(query, from, ignore_case)
             }
         }) {
             self.text_location = location;
-            self.center_text_location();
+            if self.config.search_scroll == SearchScroll::Minimal && self.is_text_location_visible() {
+                self.scroll_text_location_into_view();
+            } else {
+                self.center_text_location();
+            }
             self.set_search_found(true);
         } else {
             self.set_search_found(false);
@@ -111,10 +297,11 @@ impl View {
         self.set_needs_redraw(true);
     }
 
+    /// Overlapping matches count as separate matches, like in the match
+    /// count and backward search, so this steps one grapheme past the
+    /// current match's start rather than past its end.
     pub fn search_next(&mut self) {
-        let step_right = self
-            .get_search_query()
-            .map_or(1, |query| min(query.grapheme_count(), 1));
+        let step_right = usize::from(self.is_search_found());
         let location = Location {
             line_idx: self.text_location.line_idx,
             grapheme_idx: self.text_location.grapheme_idx.saturating_add(step_right),
@@ -126,99 +313,805 @@ impl View {
         self.search_in_direction(self.text_location, SearchDirection::Backward);
     }
 
+    /// Moves to the next line carrying an annotation of the given type.
+    /// Returns `None` if there is none, else whether the search wrapped.
+    pub fn next_annotation(&mut self, annotation_type: AnnotationType) -> Option<bool> {
+        self.jump_to_annotation(annotation_type, SearchDirection::Forward)
+    }
+
+    pub fn prev_annotation(&mut self, annotation_type: AnnotationType) -> Option<bool> {
+        self.jump_to_annotation(annotation_type, SearchDirection::Backward)
+    }
+
+    fn jump_to_annotation(
+        &mut self,
+        annotation_type: AnnotationType,
+        direction: SearchDirection,
+    ) -> Option<bool> {
+        let height = self.buffer.height();
+        let annotations = self.annotations_for(0..height);
+        let current = self.text_location.line_idx;
+        let (line_idx, byte_idx) = (1..=height).find_map(|offset| {
+            let line_idx = match direction {
+                SearchDirection::Forward => current.saturating_add(offset),
+                SearchDirection::Backward => current.saturating_add(height).saturating_sub(offset),
+            }
+            .checked_rem(height)?;
+            let (_, line_annotations) = annotations.get(line_idx)?;
+            line_annotations
+                .iter()
+                .filter(|annotation| annotation.annotation_type == annotation_type)
+                .map(|annotation| annotation.start)
+                .min()
+                .map(|byte_idx| (line_idx, byte_idx))
+        })?;
+        let wrapped = match direction {
+            SearchDirection::Forward => line_idx <= current,
+            SearchDirection::Backward => line_idx >= current,
+        };
+        self.go_to(Location {
+            line_idx,
+            grapheme_idx: self.buffer.byte_idx_to_grapheme_idx(line_idx, byte_idx),
+        });
+        Some(wrapped)
+    }
+
+    /// The annotations of each line in `line_range`, as drawn on screen:
+    /// syntax, whitespace, bracket and search highlights, with byte ranges
+    /// into the line. Lines past the end of the buffer are left out.
+    pub fn annotations_for(
+        &self,
+        line_range: Range<LineIdx>,
+    ) -> Vec<(LineIdx, Vec<Annotation>)> {
+        let end = line_range.end.min(self.buffer.height());
+        let mut highlighter = self.build_highlighter();
+        // Highlighting a line can depend on the ones above it, like inside a
+        // multi-line comment, so always start from the top.
+        for line_idx in 0..end {
+            self.buffer.highlight(line_idx, &mut highlighter);
+        }
+        (line_range.start..end)
+            .map(|line_idx| (line_idx, highlighter.get_annotations(line_idx)))
+            .collect()
+    }
+
+    fn build_highlighter(&self) -> Highlighter<'_> {
+        let query = self
+            .search_info
+            .as_ref()
+            .and_then(|search_info| search_info.query.as_deref());
+        let selected_match = if self.is_search_found() {
+            query.is_some().then_some(self.text_location)
+        } else {
+            None
+        };
+        let ignore_case = query.is_some_and(|query| self.config.search_case.ignores_case(query));
+        let mut highlighter = Highlighter::new(
+            query,
+            ignore_case,
+            selected_match,
+            self.buffer.get_file_info().get_file_type(),
+        );
+        highlighter.set_brackets(match self.find_matching_bracket() {
+            BracketMatch::Found(partner) => vec![
+                (self.text_location, AnnotationType::MatchingBracket),
+                (partner, AnnotationType::MatchingBracket),
+            ],
+            BracketMatch::Unbalanced => {
+                vec![(self.text_location, AnnotationType::UnmatchedBracket)]
+            },
+            BracketMatch::NotOnBracket => Vec::new(),
+        });
+        if self.config.highlight_trailing_whitespace {
+            highlighter.set_trailing_whitespace();
+        }
+        if let Some(diff) = &self.disk_diff {
+            highlighter.set_diff(diff);
+        }
+        if self.config.highlight_word
+            && self.edited_at != Some(self.text_location)
+            && let Some((word, start)) = self.buffer.word_at(self.text_location)
+        {
+            highlighter.set_word_occurrences(word, start);
+        }
+        highlighter
+    }
+
+    /// The grapheme under the caret; `None` at the end of a line.
+    pub fn current_grapheme(&self) -> Option<&str> {
+        self.buffer.grapheme_at(self.text_location)
+    }
+
+    pub fn find_matching_bracket(&self) -> BracketMatch {
+        self.buffer.find_matching_bracket(self.text_location)
+    }
+
+    pub fn set_mark(&mut self, name: char) -> bool {
+        if !name.is_ascii_lowercase() {
+            return false;
+        }
+        self.marks.insert(name, self.text_location);
+        true
+    }
+
+    pub fn jump_to_mark(&mut self, name: char) -> bool {
+        let Some(&location) = self.marks.get(&name) else {
+            return false;
+        };
+        self.go_to(location);
+        true
+    }
+
+    pub fn go_to(&mut self, location: Location) {
+        self.record_jump();
+        self.move_to(location);
+    }
+
+    /// Goes to display column `column` of line `line_idx`, both clamped to
+    /// the text, snapping to the nearest grapheme boundary.
+    pub fn go_to_column(&mut self, line_idx: LineIdx, column: ColIdx) {
+        let line_idx = line_idx.min(self.buffer.height().saturating_sub(1));
+        let grapheme_idx = self.buffer.grapheme_idx_near_width(line_idx, column);
+        self.go_to(Location {
+            grapheme_idx,
+            line_idx,
+        });
+    }
+
+    /// Goes back to where the caret was before the last jump.
+    pub fn jump_back(&mut self) -> bool {
+        let Some(location) = self.jump_list.back(self.text_location) else {
+            return false;
+        };
+        self.move_to(location);
+        true
+    }
+
+    pub fn jump_forward(&mut self) -> bool {
+        let Some(location) = self.jump_list.forward() else {
+            return false;
+        };
+        self.move_to(location);
+        true
+    }
+
+    /// Moves the caret to `location`, clamped to the text, and centers it.
+    fn move_to(&mut self, location: Location) {
+        self.text_location = location;
+        self.snap_to_valid_line();
+        self.snap_to_valid_grapheme();
+        self.center_text_location();
+    }
+
+    fn remember_position(&mut self) {
+        self.marks.insert(LAST_POSITION_MARK, self.text_location);
+    }
+
+    /// Like `remember_position`, and also adds the position to the jump
+    /// list. Jumps are the moves to marks and to the start or end of the
+    /// file; page moves are too frequent to be worth going back to.
+    fn record_jump(&mut self) {
+        self.remember_position();
+        self.jump_list.push(self.text_location);
+    }
+
+    /// The marks and the jump list entries, which edits have to keep on
+    /// the text they were set on.
+    fn remembered_locations_mut(&mut self) -> impl Iterator<Item = &mut Location> {
+        self.marks.values_mut().chain(self.jump_list.locations_mut())
+    }
+
+    /// Moves the marks and jumps on line `from` and below by `line_delta`
+    /// lines. When lines are joined, those landing on the line above `from`
+    /// move right by `grapheme_offset`.
+    fn shift_marks(&mut self, from: LineIdx, line_delta: isize, grapheme_offset: GraphemeIdx) {
+        for location in self.remembered_locations_mut() {
+            if location.line_idx >= from {
+                location.line_idx = location.line_idx.saturating_add_signed(line_delta);
+                if line_delta < 0 && location.line_idx < from {
+                    location.grapheme_idx = location.grapheme_idx.saturating_add(grapheme_offset);
+                }
+            }
+        }
+    }
+
     pub fn load(&mut self, file_name: &str) -> Result<(), Error> {
         let buffer = Buffer::load(file_name)?;
+        self.replace_buffer(buffer);
+        Ok(())
+    }
+
+    pub fn load_lossy(&mut self, file_name: &str) -> Result<(), Error> {
+        let buffer = Buffer::load_lossy(file_name)?;
+        self.replace_buffer(buffer);
+        Ok(())
+    }
+
+    pub const fn is_lossy(&self) -> bool {
+        self.buffer.is_lossy()
+    }
+
+    pub fn set_text(&mut self, contents: &str) {
+        self.replace_buffer(Buffer::from_text(contents));
+    }
+
+    fn replace_buffer(&mut self, buffer: Buffer) {
         self.buffer = buffer;
+        self.text_location = Location::default();
+        self.scroll_offset = Position::default();
+        self.marks.clear();
+        self.jump_list = JumpList::default();
+        self.disk_diff = None;
+        self.refresh_config();
         self.set_needs_redraw(true);
-        Ok(())
     }
 
     pub fn save(&mut self) -> Result<(), Error> {
+        self.trim_before_save();
         self.buffer.save()?;
         self.set_needs_redraw(true);
         Ok(())
     }
 
+    /// Drops trailing whitespace if `trim_trailing_whitespace` is set.
+    fn trim_before_save(&mut self) {
+        if self.config.trim_trailing_whitespace
+            && !self.buffer.is_read_only()
+            && !self.buffer.trim_trailing_whitespace().is_empty()
+        {
+            self.disk_diff = None;
+            self.snap_to_valid_grapheme();
+        }
+    }
+
+    /// Writes the document being edited, never the help text shown over it.
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<(), Error> {
+        self.stashed
+            .as_ref()
+            .map_or(&self.buffer, |stashed| &stashed.buffer)
+            .write_to(writer)
+    }
+
+    /// Writes the buffer with its syntax highlighting as an HTML page.
+    /// The buffer itself is left untouched.
+    pub fn export_html(&self, file_name: &str) -> Result<(), Error> {
+        let mut highlighter =
+            Highlighter::new(None, false, None, self.buffer.get_file_info().get_file_type());
+        if self.config.highlight_trailing_whitespace {
+            highlighter.set_trailing_whitespace();
+        }
+        for line_idx in 0..self.buffer.height() {
+            self.buffer.highlight(line_idx, &mut highlighter);
+        }
+        fs::write(file_name, self.buffer.to_html(&highlighter))
+    }
+
+    /// Shows how the buffer differs from its file on disk, or hides the
+    /// differences if they are shown. Returns the number of changed, added
+    /// and removed lines, or `None` when hiding.
+    pub fn toggle_disk_diff(&mut self) -> Result<Option<(usize, usize, usize)>, Error> {
+        self.set_needs_redraw(true);
+        if self.disk_diff.take().is_some() {
+            return Ok(None);
+        }
+        let diff = self.buffer.diff_against_file()?;
+        let counts = (
+            diff.count(LineChange::Changed),
+            diff.count(LineChange::Added),
+            diff.removed_count(),
+        );
+        self.disk_diff = Some(diff);
+        Ok(Some(counts))
+    }
+
+    pub fn get_file_path(&self) -> Option<&Path> {
+        self.buffer.get_file_info().get_path()
+    }
+
     pub fn save_as(&mut self, file_name: &str) -> Result<(), Error> {
+        self.trim_before_save();
         self.buffer.save_as(file_name)?;
+        self.refresh_config();
         self.set_needs_redraw(true);
         Ok(())
     }
 
     pub fn handle_edit_command(&mut self, command: Edit) {
+        if self.buffer.is_read_only() {
+            return;
+        }
         match command {
             Edit::DeleteBackward => self.delete_backward(),
             Edit::Delete => self.delete(),
             Edit::InsertNewline => self.insert_newline(),
             Edit::Insert(character) => self.insert_char(character),
+            Edit::KillLine => self.kill_line(),
+            Edit::Transpose => self.transpose_chars(),
+            Edit::MoveLineUp => self.move_line_up(),
+            Edit::MoveLineDown => self.move_line_down(),
+            Edit::OpenLineBelow => self.open_line(self.text_location.line_idx.saturating_add(1)),
+            Edit::OpenLineAbove => self.open_line(self.text_location.line_idx),
+            Edit::ChangeCase(case_change) => self.change_case(case_change),
         }
+        self.edited_at = Some(self.text_location);
+        self.disk_diff = None;
     }
 
     pub fn handle_move_command(&mut self, command: Move) {
         let Size { height, .. } = self.size;
+        let from = self.text_location;
         match command {
             Move::Up => self.move_up(1),
             Move::Down => self.move_down(1),
-            Move::PageUp => self.move_up(height.saturating_sub(1)),
-            Move::PageDown => self.move_down(height.saturating_sub(1)),
+            Move::PageUp => {
+                self.remember_position();
+                self.move_up(height.saturating_sub(1));
+            },
+            Move::PageDown => {
+                self.remember_position();
+                self.move_down(height.saturating_sub(1));
+            },
             Move::Left => self.move_left(),
             Move::Right => self.move_right(),
             Move::StartOfLine => self.move_to_start_of_line(),
             Move::EndOfLine => self.move_to_end_of_line(),
+            Move::ParagraphUp => self.move_paragraph_up(),
+            Move::ParagraphDown => self.move_paragraph_down(),
+            Move::StartOfFile => {
+                self.record_jump();
+                self.text_location = Location::default();
+            },
+            Move::EndOfFile => {
+                self.record_jump();
+                let last_line_idx = self.buffer.height().saturating_sub(1);
+                self.text_location = Location {
+                    line_idx: last_line_idx,
+                    grapheme_idx: self.buffer.grapheme_count(last_line_idx),
+                };
+            },
         }
         self.scroll_text_location_into_view();
+        self.redraw_after_caret_move(from);
+    }
+
+    /// The bracket and word highlights follow the caret, but a move that
+    /// doesn't scroll wouldn't redraw otherwise.
+    fn redraw_after_caret_move(&mut self, from: Location) {
+        if from != self.text_location
+            && (self.config.highlight_word
+                || self.buffer.is_bracket_at(from)
+                || self.buffer.is_bracket_at(self.text_location))
+        {
+            self.set_needs_redraw(true);
+        }
     }
+
     fn insert_newline(&mut self) {
+        if self.is_inside_empty_block() {
+            self.expand_block();
+            return;
+        }
+        let old_height = self.buffer.height();
         self.buffer.insert_newline(self.text_location);
+        if self.buffer.height() > old_height {
+            self.shift_marks(self.text_location.line_idx.saturating_add(1), 1, 0);
+        }
         self.handle_move_command(Move::Right);
         self.set_needs_redraw(true);
     }
 
+    fn is_inside_empty_block(&self) -> bool {
+        let Location {
+            line_idx,
+            grapheme_idx,
+        } = self.text_location;
+        let Some(before) = grapheme_idx.checked_sub(1) else {
+            return false;
+        };
+        let before = self.buffer.grapheme_at(Location {
+            line_idx,
+            grapheme_idx: before,
+        });
+        let after = self.current_grapheme();
+        matches!(
+            (before, after),
+            (Some("("), Some(")")) | (Some("["), Some("]")) | (Some("{"), Some("}"))
+        )
+    }
+
+    /// Inserts a line before line `at` with the current line's indentation
+    /// and moves the cursor to its end. The current line is not split.
+    fn open_line(&mut self, at: LineIdx) {
+        let indentation = self
+            .buffer
+            .indentation(self.text_location.line_idx)
+            .to_string();
+        self.buffer.insert_line(at, &indentation);
+        self.shift_marks(at, 1, 0);
+        self.text_location = Location {
+            line_idx: at,
+            grapheme_idx: self.buffer.grapheme_count(at),
+        };
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+
+    /// Puts the closing bracket on its own line at the opening line's
+    /// indentation, leaving the cursor on an indented line in between.
+    fn expand_block(&mut self) {
+        let line_idx = self.text_location.line_idx;
+        let indentation = self.buffer.indentation(line_idx).to_string();
+        let inner_indentation = if self.config.expand_tabs {
+            format!("{indentation}{}", " ".repeat(self.config.tab_width))
+        } else {
+            format!("{indentation}\t")
+        };
+        let inner_line_idx = line_idx.saturating_add(1);
+        self.buffer.insert_newline(self.text_location);
+        self.buffer.insert_newline(Location {
+            line_idx: inner_line_idx,
+            grapheme_idx: 0,
+        });
+        self.shift_marks(inner_line_idx, 2, 0);
+        self.buffer.insert_str(
+            &indentation,
+            Location {
+                line_idx: inner_line_idx.saturating_add(1),
+                grapheme_idx: 0,
+            },
+        );
+        self.buffer.insert_str(
+            &inner_indentation,
+            Location {
+                line_idx: inner_line_idx,
+                grapheme_idx: 0,
+            },
+        );
+        self.text_location = Location {
+            line_idx: inner_line_idx,
+            grapheme_idx: self.buffer.grapheme_count(inner_line_idx),
+        };
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+
+    /// Inside indentation made of spaces, deletes back to the previous tab
+    /// stop instead of a single space.
     fn delete_backward(&mut self) {
-        if self.text_location.line_idx != 0 || self.text_location.grapheme_idx != 0 {
-            self.handle_move_command(Move::Left);
-            self.delete();
+        let Location {
+            line_idx,
+            grapheme_idx,
+        } = self.text_location;
+        let in_indent = grapheme_idx > 0
+            && (0..grapheme_idx).all(|grapheme_idx| {
+                self.buffer.grapheme_at(Location {
+                    grapheme_idx,
+                    line_idx,
+                }) == Some(" ")
+            });
+        let count = if in_indent {
+            grapheme_idx
+                .saturating_sub(1)
+                .checked_rem(self.config.tab_width)
+                .unwrap_or(0)
+                .saturating_add(1)
+        } else {
+            1
+        };
+        for _ in 0..count {
+            if self.text_location.line_idx != 0 || self.text_location.grapheme_idx != 0 {
+                self.handle_move_command(Move::Left);
+                self.delete();
+            }
         }
     }
 
     fn delete(&mut self) {
+        let old_height = self.buffer.height();
+        let Location { line_idx, .. } = self.text_location;
+        let joined_at = self.buffer.grapheme_count(line_idx);
         self.buffer.delete(self.text_location);
+        if self.buffer.height() < old_height {
+            self.shift_marks(line_idx.saturating_add(1), -1, joined_at);
+        }
+        self.snap_to_valid_line();
+        self.snap_to_valid_grapheme();
         self.set_needs_redraw(true);
     }
 
-    fn insert_char(&mut self, character: char) {
-        let old_len = self.buffer.grapheme_count(self.text_location.line_idx);
-        self.buffer.insert_char(character, self.text_location);
-        let new_len = self.buffer.grapheme_count(self.text_location.line_idx);
-        let grapheme_delta = new_len.saturating_sub(old_len);
-        if grapheme_delta > 0 {
+    // Like Emacs' Ctrl-K: at the end of a line this joins the next line.
+    fn kill_line(&mut self) {
+        let Location {
+            line_idx,
+            grapheme_idx,
+        } = self.text_location;
+        if grapheme_idx >= self.buffer.grapheme_count(line_idx) {
+            self.delete();
+        } else {
+            self.buffer.delete_to_end_of_line(self.text_location);
+            self.set_needs_redraw(true);
+        }
+    }
+
+    // Like Emacs' Ctrl-T: swaps the graphemes around the cursor and moves
+    // right; at the end of a line the last two are swapped instead.
+    fn transpose_chars(&mut self) {
+        let Location {
+            line_idx,
+            grapheme_idx,
+        } = self.text_location;
+        let grapheme_count = self.buffer.grapheme_count(line_idx);
+        if grapheme_idx == 0 || grapheme_count < 2 {
+            return;
+        }
+        let left = min(grapheme_idx, grapheme_count.saturating_sub(1)).saturating_sub(1);
+        self.buffer.swap_graphemes(Location {
+            line_idx,
+            grapheme_idx: left,
+        });
+        if grapheme_idx < grapheme_count {
             self.handle_move_command(Move::Right);
         }
         self.set_needs_redraw(true);
     }
-    fn render_line(at: RowIdx, line_text: &str) -> Result<(), Error> {
-        Terminal::print_row(at, line_text)
+
+    /// Replaces the word at the caret as a whole, since changing case can
+    /// change its length. A caret at the end of the word stays at the end.
+    fn change_case(&mut self, case_change: CaseChange) {
+        let Some((word, start)) = self.buffer.word_at(self.text_location) else {
+            return;
+        };
+        let old_len = word.graphemes(true).count();
+        let changed = case_change.apply(word);
+        let new_len = changed.graphemes(true).count();
+        let old_end = start.grapheme_idx.saturating_add(old_len);
+        let new_end = start.grapheme_idx.saturating_add(new_len);
+        self.buffer
+            .replace_range(start.line_idx, start.grapheme_idx..old_end, &changed);
+        if self.text_location.grapheme_idx == old_end || self.text_location.grapheme_idx > new_end {
+            self.text_location.grapheme_idx = new_end;
+        }
+        self.set_needs_redraw(true);
+    }
+
+    fn move_line_up(&mut self) {
+        let line_idx = self.text_location.line_idx;
+        if line_idx > 0 && line_idx < self.buffer.height() {
+            self.swap_lines(line_idx, line_idx.saturating_sub(1));
+        }
+    }
+
+    fn move_line_down(&mut self) {
+        let line_idx = self.text_location.line_idx;
+        if line_idx.saturating_add(1) < self.buffer.height() {
+            self.swap_lines(line_idx, line_idx.saturating_add(1));
+        }
     }
 
-    fn build_welcome_message(width: usize) -> String {
+    /// Collapses each run of empty lines into one. The caret and marks stay
+    /// with their text; on a removed line they move to the empty line that
+    /// is kept. Returns the number of removed lines, or `None` if the buffer
+    /// is read-only.
+    pub fn squeeze_blank_lines(&mut self) -> Option<usize> {
+        if self.buffer.is_read_only() {
+            return None;
+        }
+        let removed = self.buffer.squeeze_blank_lines();
+        self.disk_diff = None;
+        let squeeze = |location: Location| {
+            let removed_above = removed.partition_point(|&idx| idx < location.line_idx);
+            let line_idx = location.line_idx.saturating_sub(removed_above);
+            if removed.binary_search(&location.line_idx).is_ok() {
+                Location {
+                    line_idx: line_idx.saturating_sub(1),
+                    grapheme_idx: 0,
+                }
+            } else {
+                Location {
+                    line_idx,
+                    ..location
+                }
+            }
+        };
+        self.text_location = squeeze(self.text_location);
+        for location in self.remembered_locations_mut() {
+            *location = squeeze(*location);
+        }
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+        Some(removed.len())
+    }
+
+    /// Converts the indentation to spaces, or to tabs with `to_tabs`,
+    /// keeping the caret and marks at their columns. Returns how many lines
+    /// changed, or `None` if the buffer is read-only.
+    pub fn retab(&mut self, to_tabs: bool) -> Option<usize> {
+        if self.buffer.is_read_only() {
+            return None;
+        }
+        let width_of = |buffer: &Buffer, location: Location| {
+            buffer.width_until(location.line_idx, location.grapheme_idx)
+        };
+        let caret_width = width_of(&self.buffer, self.text_location);
+        let mark_widths: Vec<(char, ColIdx)> = self
+            .marks
+            .iter()
+            .map(|(&name, &location)| (name, width_of(&self.buffer, location)))
+            .collect();
+        let changed = if to_tabs {
+            self.buffer.spaces_to_tabs()
+        } else {
+            self.buffer.tabs_to_spaces(self.config.convert_all_tabs)
+        };
+        if changed.is_empty() {
+            return Some(0);
+        }
+        self.disk_diff = None;
+        let remap = |buffer: &Buffer, location: &mut Location, width: ColIdx| {
+            if changed.binary_search(&location.line_idx).is_ok() {
+                location.grapheme_idx = buffer.grapheme_idx_at_width(location.line_idx, width);
+            }
+        };
+        remap(&self.buffer, &mut self.text_location, caret_width);
+        for (name, width) in mark_widths {
+            if let Some(location) = self.marks.get_mut(&name) {
+                remap(&self.buffer, location, width);
+            }
+        }
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+        Some(changed.len())
+    }
+
+    // The cursor and any marks travel with their lines.
+    fn swap_lines(&mut self, from: LineIdx, to: LineIdx) {
+        self.buffer.swap_lines(from, to);
+        for location in self.remembered_locations_mut() {
+            if location.line_idx == from {
+                location.line_idx = to;
+            } else if location.line_idx == to {
+                location.line_idx = from;
+            }
+        }
+        self.text_location.line_idx = to;
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+
+    /// Inserts `text` at the caret as one edit, leaving the caret after it.
+    pub fn paste(&mut self, text: &str) {
+        if self.buffer.is_read_only() {
+            return;
+        }
+        let text: String = text
+            .chars()
+            .filter(|&character| {
+                matches!(character, '\n' | '\r') || !self.rejects_char(character)
+            })
+            .collect();
+        let old_height = self.buffer.height();
+        let end = self.buffer.insert_str(&text, self.text_location);
+        let added_lines = self.buffer.height().saturating_sub(old_height);
+        if added_lines > 0 {
+            self.shift_marks(
+                self.text_location.line_idx.saturating_add(1),
+                isize::try_from(added_lines).unwrap_or(isize::MAX),
+                0,
+            );
+        }
+        self.text_location = end;
+        self.edited_at = Some(end);
+        self.disk_diff = None;
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+
+    /// Whether typing `character` is refused: control characters other
+    /// than tab, unless `filter_control_chars` is turned off.
+    pub fn rejects_char(&self, character: char) -> bool {
+        self.config.filter_control_chars && character.is_control() && character != '\t'
+    }
+
+    /// Inserts `character` as is: no control character filter and no tab
+    /// expansion.
+    pub fn insert_literal(&mut self, character: char) {
+        if !self.buffer.is_read_only() {
+            self.insert_raw_char(character);
+            self.edited_at = Some(self.text_location);
+            self.disk_diff = None;
+        }
+    }
+
+    fn insert_char(&mut self, character: char) {
+        if self.rejects_char(character) {
+            return;
+        }
+        if character == '\t' && self.config.expand_tabs {
+            // Pad to the next tab stop of the rendered column, so earlier
+            // hard tabs and wide characters are accounted for.
+            let tab_width = self.config.tab_width;
+            let column = self.text_location_to_position().col;
+            let spaces = tab_width.saturating_sub(column.checked_rem(tab_width).unwrap_or(0));
+            for _ in 0..spaces {
+                self.insert_raw_char(' ');
+            }
+            return;
+        }
+        self.insert_raw_char(character);
+    }
+
+    fn insert_raw_char(&mut self, character: char) {
+        self.text_location = self.buffer.insert_char(character, self.text_location);
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+    fn filler(&self) -> String {
+        self.config.filler.map(String::from).unwrap_or_default()
+    }
+
+    fn build_welcome_message(&self, width: usize) -> String {
+        let welcome_message = format!("{NAME} editor -- version {VERSION}");
+        self.center_on_filler_line(&welcome_message, width)
+    }
+
+    /// The filler in the first column, `text` centered in the rest.
+    fn center_on_filler_line(&self, text: &str, width: usize) -> String {
         if width == 0 {
             return String::new();
         }
-        let welcome_message = format!("{NAME} editor -- version {VERSION}");
-        let len = welcome_message.len();
+        let filler = self.filler();
+        let len = text.chars().count();
         let remaining_width = width.saturating_sub(1);
         if remaining_width < len {
-            return "~".to_string();
+            return filler;
         }
-        format!("{:1<}{:^remaining_width$}", "~", welcome_message)
+        format!("{filler:<1}{text:^remaining_width$}")
     }
-    fn scroll_vertically(&mut self, to: RowIdx) {
+
+    fn build_welcome_lines(&self, width: usize) -> Vec<String> {
+        let mut lines = vec![self.build_welcome_message(width)];
+        if self.recent_files.is_empty() {
+            return lines;
+        }
+        let mut block = vec![String::from("Recent files (F2 and a number to open):")];
+        block.extend(
+            self.recent_files
+                .iter()
+                .enumerate()
+                .map(|(idx, path)| format!("{}  {path}", idx.saturating_add(1))),
+        );
+        let block_width = block
+            .iter()
+            .map(|entry| entry.chars().count())
+            .max()
+            .unwrap_or_default();
+        lines.push(self.filler());
+        for entry in block {
+            lines.push(self.center_on_filler_line(
+                &format!("{entry:<block_width$}"),
+                width,
+            ));
+        }
+        lines
+    }
+    // The scroll helpers take the whole position and read their own axis by
+    // name, so callers can't hand over a bare column as a row.
+    fn scroll_vertically(&mut self, to: Position) {
         let Size { height, .. } = self.size;
-        let offset_changed = if to < self.scroll_offset.row {
-            self.scroll_offset.row = to;
+        let to = to.row();
+        // Never more than half the view, or the margins would fight each other.
+        let margin = min(
+            self.config.scroll_margin,
+            height.saturating_sub(1).checked_div(2).unwrap_or(0),
+        );
+        let offset_changed = if to < self.scroll_offset.row.saturating_add(margin) {
+            self.scroll_offset.row = to.saturating_sub(margin);
             true
-        } else if to >= self.scroll_offset.row.saturating_add(height) {
-            self.scroll_offset.row = to.saturating_sub(height).saturating_add(1);
+        } else if to.saturating_add(margin) >= self.scroll_offset.row.saturating_add(height) {
+            self.scroll_offset.row = to
+                .saturating_add(margin)
+                .saturating_sub(height)
+                .saturating_add(1);
             true
         } else {
             false
@@ -228,8 +1121,9 @@ impl View {
         }
     }
 
-    fn scroll_horizontally(&mut self, to: ColIdx) {
-        let Size { width, .. } = self.size;
+    fn scroll_horizontally(&mut self, to: Position) {
+        let width = self.text_width();
+        let to = to.col();
         let offset_changed = if to < self.scroll_offset.col {
             self.scroll_offset.col = to;
             true
@@ -245,32 +1139,98 @@ impl View {
     }
 
     fn center_text_location(&mut self) {
-        let Size { height, width } = self.size;
+        let Size { height, .. } = self.size;
+        let width = self.text_width();
         let Position { col, row } = self.text_location_to_position();
-        let vertical_mid = height.div_ceil(2);
+        let caret_row = height
+            .saturating_mul(self.config.center_percent)
+            .div_ceil(100)
+            .min(height.saturating_sub(1));
         let horizontal_mid = width.div_ceil(2);
-        self.scroll_offset.row = row.saturating_sub(vertical_mid);
+        self.scroll_offset.row = row.saturating_sub(caret_row);
         self.scroll_offset.col = col.saturating_sub(horizontal_mid);
         self.set_needs_redraw(true);
     }
 
+    fn is_text_location_visible(&self) -> bool {
+        let Size { height, .. } = self.size;
+        let width = self.text_width();
+        let Position { col, row } = self.text_location_to_position();
+        (self.scroll_offset.row..self.scroll_offset.row.saturating_add(height)).contains(&row)
+            && (self.scroll_offset.col..self.scroll_offset.col.saturating_add(width)).contains(&col)
+    }
+
     fn scroll_text_location_into_view(&mut self) {
-        let Position { row, col } = self.text_location_to_position();
-        self.scroll_vertically(row);
-        self.scroll_horizontally(col);
+        let position = self.text_location_to_position();
+        self.scroll_vertically(position);
+        self.scroll_horizontally(position);
     }
     pub fn caret_position(&self) -> Position {
-        self.text_location_to_position()
-            .saturating_sub(self.scroll_offset)
+        let position = self
+            .text_location_to_position()
+            .saturating_sub(self.scroll_offset);
+        Position::new(position.row(), position.col().saturating_add(self.gutter_width()))
+    }
+
+    fn gutter_width(&self) -> usize {
+        if self.config.change_gutter {
+            GUTTER_WIDTH
+        } else {
+            0
+        }
+    }
+
+    /// The columns left for text next to the gutter.
+    fn text_width(&self) -> usize {
+        self.size.width.saturating_sub(self.gutter_width())
+    }
+
+    /// Shows or hides the change gutter for this and later buffers.
+    /// Returns whether it is shown now.
+    pub fn toggle_change_gutter(&mut self) -> bool {
+        let shown = !self.config.change_gutter;
+        self.config_file
+            .set_override("change_gutter", if shown { "true" } else { "false" });
+        self.refresh_config();
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+        shown
+    }
+
+    /// `+` for an added line, `~` for a changed one, `-` below removed
+    /// lines and `_` on the last line if the removed lines were at the end.
+    fn gutter_sign(changes: &LineDiff, line_idx: LineIdx, is_last: bool) -> AnnotatedString {
+        let (sign, annotation_type) = match changes.change(line_idx) {
+            LineChange::Added => ("+", Some(AnnotationType::DiffAdded)),
+            LineChange::Changed => ("~", Some(AnnotationType::DiffChanged)),
+            LineChange::Unchanged if changes.removed_before(line_idx) > 0 => {
+                ("-", Some(AnnotationType::DiffRemoved))
+            },
+            LineChange::Unchanged
+                if is_last && changes.removed_before(line_idx.saturating_add(1)) > 0 =>
+            {
+                ("_", Some(AnnotationType::DiffRemoved))
+            },
+            LineChange::Unchanged => (" ", None),
+        };
+        let mut gutter = AnnotatedString::from(&format!("{sign} "));
+        if let Some(annotation_type) = annotation_type {
+            gutter.add_annotation(annotation_type, 0, sign.len());
+        }
+        gutter
     }
 
     fn text_location_to_position(&self) -> Position {
         let row = self.text_location.line_idx;
-        debug_assert!(row.saturating_sub(1) <= self.buffer.height());
+        debug_assert!(
+            row <= self.buffer.height(),
+            "Cursor line {row} is past the end of a buffer with {} lines",
+            self.buffer.height()
+        );
         let col = self
             .buffer
             .width_until(row, self.text_location.grapheme_idx);
-        Position { col, row }
+        Position::new(row, col)
     }
     fn move_up(&mut self, step: usize) {
         self.text_location.line_idx = self.text_location.line_idx.saturating_sub(step);
@@ -288,7 +1248,7 @@ impl View {
         let grapheme_count = self.buffer.grapheme_count(self.text_location.line_idx);
         if self.text_location.grapheme_idx < grapheme_count {
             self.text_location.grapheme_idx += 1;
-        } else {
+        } else if self.text_location.line_idx + 1 < self.buffer.height() {
             self.move_to_start_of_line();
             self.move_down(1);
         }
@@ -304,6 +1264,31 @@ impl View {
         }
     }
 
+    fn move_paragraph_up(&mut self) {
+        let line_idx = (0..self.text_location.line_idx)
+            .rev()
+            .find(|&idx| self.buffer.is_blank(idx))
+            .unwrap_or(0);
+        self.text_location = Location {
+            grapheme_idx: 0,
+            line_idx,
+        };
+    }
+
+    fn move_paragraph_down(&mut self) {
+        let last_line_idx = self.buffer.height().saturating_sub(1);
+        let start = self.text_location.line_idx.saturating_add(1);
+        if let Some(line_idx) = (start..=last_line_idx).find(|&idx| self.buffer.is_blank(idx)) {
+            self.text_location = Location {
+                grapheme_idx: 0,
+                line_idx,
+            };
+        } else {
+            self.text_location.line_idx = last_line_idx;
+            self.move_to_end_of_line();
+        }
+    }
+
     fn move_to_start_of_line(&mut self) {
         self.text_location.grapheme_idx = 0;
     }
@@ -318,8 +1303,13 @@ impl View {
         );
     }
 
+    // The last line is the lowest the caret goes. Only an empty buffer puts
+    // it at `height()`, which is where `Buffer::insert_char` appends a line.
     fn snap_to_valid_line(&mut self) {
-        self.text_location.line_idx = min(self.text_location.line_idx, self.buffer.height());
+        self.text_location.line_idx = min(
+            self.text_location.line_idx,
+            self.buffer.height().saturating_sub(1),
+        );
     }
 }
 impl UIComponent for View {
@@ -336,51 +1326,276 @@ impl UIComponent for View {
         self.scroll_text_location_into_view();
     }
 
-    fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error> {
+    fn draw(&mut self, renderer: &mut dyn Renderer, origin_row: RowIdx) -> Result<(), Error> {
         let Size { height, width } = self.size;
+        let text_width = self.text_width();
+        let changes = self
+            .config
+            .change_gutter
+            .then(|| self.buffer.changes_since_baseline());
         let end_y = origin_row.saturating_add(height);
         let top_third = height.div_ceil(3);
         let scroll_top = self.scroll_offset.row;
 
-        let query = self
-            .search_info
-            .as_ref()
-            .and_then(|search_info| search_info.query.as_deref());
-        let selected_match = if self.is_search_found() {
-            query.is_some().then_some(self.text_location)
-        } else {
-            None
-        };
-        let mut highlighter = Highlighter::new(
-            query,
-            selected_match,
-            self.buffer.get_file_info().get_file_type(),
-        );
-
-        for current_row in origin_row..end_y {
-            let line_idx = current_row
-                .saturating_sub(origin_row)
-                .saturating_add(scroll_top);
+        let mut highlighter = self.build_highlighter();
+        for line_idx in 0..scroll_top.saturating_add(height) {
             self.buffer.highlight(line_idx, &mut highlighter);
         }
 
+        let welcome_lines = if self.is_showing_welcome() {
+            self.build_welcome_lines(width)
+        } else {
+            Vec::new()
+        };
+        let filler = self.filler();
+
         for current_row in origin_row..end_y {
             let line_idx = current_row
                 .saturating_sub(origin_row)
                 .saturating_add(scroll_top);
+            let welcome_line = current_row
+                .checked_sub(origin_row.saturating_add(top_third))
+                .and_then(|idx| welcome_lines.get(idx));
             let left = self.scroll_offset.col;
-            let right = self.scroll_offset.col.saturating_add(width);
+            let right = self.scroll_offset.col.saturating_add(text_width);
             if let Some(annotated_string) =
                 self.buffer
                     .get_highlighted_substring(line_idx, left..right, &highlighter)
             {
-                Terminal::print_annotated_row(current_row, &annotated_string)?;
-            } else if current_row == top_third && self.buffer.is_empty() {
-                Self::render_line(current_row, &Self::build_welcome_message(width))?;
+                if let Some(changes) = changes {
+                    let is_last = line_idx.saturating_add(1) == self.buffer.height();
+                    let gutter = Self::gutter_sign(changes, line_idx, is_last);
+                    renderer.print_annotated_parts(current_row, &[&gutter, &annotated_string])?;
+                } else {
+                    renderer.print_annotated_row(current_row, &annotated_string)?;
+                }
+            } else if let Some(welcome_line) = welcome_line {
+                renderer.print_row(current_row, welcome_line)?;
             } else {
-                Self::render_line(current_row, "~")?;
+                renderer.print_row(current_row, &filler)?;
             }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view_with(text: &str) -> View {
+        let mut view = View::default();
+        view.resize(Size {
+            height: 10,
+            width: 40,
+        });
+        view.set_text(text);
+        view.set_needs_redraw(false);
+        view
+    }
+
+    #[test]
+    fn moving_onto_or_off_a_bracket_redraws() {
+        let mut view = view_with("(a) b");
+        view.handle_move_command(Move::Right);
+        assert!(view.needs_redraw(), "moving off a bracket");
+
+        view.set_needs_redraw(false);
+        view.handle_move_command(Move::Right);
+        assert!(view.needs_redraw(), "moving onto a bracket");
+
+        view.set_needs_redraw(false);
+        view.handle_move_command(Move::Right);
+        view.set_needs_redraw(false);
+        view.handle_move_command(Move::Right);
+        assert!(!view.needs_redraw(), "moving between plain characters");
+    }
+
+    #[test]
+    fn moving_with_highlight_word_redraws() {
+        let mut view = view_with("one two one");
+        view.config.highlight_word = true;
+        view.handle_move_command(Move::Right);
+        assert!(view.needs_redraw());
+
+        view.set_needs_redraw(false);
+        view.handle_move_command(Move::EndOfLine);
+        assert!(view.needs_redraw());
+
+        view.set_needs_redraw(false);
+        view.handle_move_command(Move::EndOfLine);
+        assert!(!view.needs_redraw(), "the caret did not move");
+    }
+
+    fn line(line_idx: LineIdx) -> Location {
+        Location {
+            grapheme_idx: 0,
+            line_idx,
+        }
+    }
+
+    #[test]
+    fn opening_a_line_shifts_marks_and_jumps() {
+        let mut view = view_with("a\nb\nc");
+        view.go_to(line(2));
+        view.set_mark('a');
+        view.go_to(line(0));
+        view.handle_edit_command(Edit::OpenLineAbove);
+
+        assert_eq!(view.marks.get(&'a'), Some(&line(3)));
+        assert!(view.jump_back());
+        assert_eq!(view.text_location, line(3));
+        assert!(view.jump_back());
+        assert_eq!(view.text_location, line(1));
+    }
+
+    #[test]
+    fn squeezing_and_swapping_lines_move_jumps() {
+        let mut view = view_with("a\n\n\n\nb\nc");
+        view.go_to(line(4));
+        view.go_to(line(5));
+        assert_eq!(view.squeeze_blank_lines(), Some(2));
+        assert!(view.jump_back());
+        assert_eq!(view.text_location, line(2), "from line 4 after squeezing");
+
+        view.go_to(line(3));
+        view.handle_edit_command(Edit::MoveLineUp);
+        assert_eq!(view.text_location, line(2));
+        assert!(view.jump_back());
+        assert_eq!(view.text_location, line(3), "from line 2 after swapping");
+    }
+
+    #[test]
+    fn search_next_and_prev_visit_every_overlapping_match() {
+        let mut view = view_with("aaaa\naa");
+        view.enter_search();
+        view.search("aa");
+        let expected = [(0, 0), (0, 1), (0, 2), (1, 0)].map(|(line_idx, grapheme_idx)| Location {
+            grapheme_idx,
+            line_idx,
+        });
+        assert_eq!(view.text_location, expected[0]);
+        assert_eq!(view.search_match_summary(), "(1/4)");
+
+        for (idx, location) in expected.iter().enumerate().skip(1) {
+            view.search_next();
+            assert_eq!(view.text_location, *location);
+            assert_eq!(view.search_match_summary(), format!("({}/4)", idx.saturating_add(1)));
+        }
+        view.search_next();
+        assert_eq!(view.text_location, expected[0], "wraps around");
+
+        view.search_prev();
+        assert_eq!(view.search_match_summary(), "(4/4)", "wraps back");
+        for (idx, location) in expected.iter().enumerate().rev().skip(1) {
+            view.search_prev();
+            assert_eq!(view.text_location, *location);
+            assert_eq!(view.search_match_summary(), format!("({}/4)", idx.saturating_add(1)));
+        }
+    }
+
+    /// The caret must stay on an existing line, or on line 0 once the
+    /// buffer is empty, and within that line.
+    fn assert_caret_valid(view: &View) {
+        let Location {
+            grapheme_idx,
+            line_idx,
+        } = view.text_location;
+        assert!(line_idx < view.buffer.height().max(1), "line {line_idx}");
+        assert!(grapheme_idx <= view.buffer.grapheme_count(line_idx));
+    }
+
+    #[test]
+    fn deleting_everything_keeps_the_caret_valid() {
+        let mut view = view_with("ab\ncd\nef");
+        view.text_location = Location {
+            grapheme_idx: 2,
+            line_idx: 2,
+        };
+        while view.text_location != line(0) {
+            view.handle_edit_command(Edit::DeleteBackward);
+            assert_caret_valid(&view);
+        }
+        assert_eq!(view.buffer.height(), 1);
+        assert_eq!(view.buffer.grapheme_count(0), 0);
+
+        let mut view = view_with("ab\ncd\nef");
+        for _ in 0..8 {
+            view.handle_edit_command(Edit::Delete);
+            assert_caret_valid(&view);
+        }
+        assert_eq!(view.buffer.height(), 1);
+        assert_eq!(view.buffer.grapheme_count(0), 0);
+        assert_eq!(view.text_location, line(0));
+    }
+
+    #[test]
+    fn scrolling_keeps_rows_and_columns_apart() {
+        let text = format!("{}\n{}", "x".repeat(60), "ab\n".repeat(30));
+        let mut view = view_with(&text);
+
+        view.text_location = Location {
+            grapheme_idx: 2,
+            line_idx: 25,
+        };
+        view.scroll_text_location_into_view();
+        assert_eq!(view.scroll_offset.col, 0);
+        assert_eq!(view.scroll_offset.row, 16);
+
+        view.text_location = Location {
+            grapheme_idx: 50,
+            line_idx: 0,
+        };
+        view.scroll_text_location_into_view();
+        assert_eq!(view.scroll_offset.row, 0);
+        assert_eq!(view.scroll_offset.col, 11);
+    }
+
+    #[test]
+    fn backspace_removes_before_the_caret_and_joins_lines() {
+        let mut view = view_with("ab\ncd");
+        view.text_location = Location {
+            grapheme_idx: 1,
+            line_idx: 1,
+        };
+        view.handle_edit_command(Edit::DeleteBackward);
+        assert_eq!(view.buffer.grapheme_count(1), 1);
+        assert_eq!(view.buffer.grapheme_at(line(1)), Some("d"));
+        assert_eq!(view.text_location, line(1));
+
+        view.handle_edit_command(Edit::DeleteBackward);
+        assert_eq!(view.buffer.height(), 1);
+        assert_eq!(view.buffer.grapheme_count(0), 3);
+        let joined = Location {
+            grapheme_idx: 2,
+            line_idx: 0,
+        };
+        assert_eq!(view.buffer.grapheme_at(joined), Some("d"));
+        assert_eq!(view.text_location, joined);
+    }
+
+    #[test]
+    fn trailing_whitespace_highlight_follows_the_config() {
+        let path = std::env::temp_dir().join(format!("hecto-{}-export.html", std::process::id()));
+        let has_highlight = |view: &View| {
+            view.annotations_for(0..1)
+                .iter()
+                .flat_map(|(_, annotations)| annotations)
+                .any(|annotation| annotation.annotation_type == AnnotationType::TrailingWhitespace)
+        };
+        let exported = |view: &View| {
+            view.export_html(path.to_str().unwrap()).unwrap();
+            fs::read_to_string(&path).unwrap()
+        };
+
+        let mut view = view_with("a  ");
+        assert!(has_highlight(&view));
+        assert!(exported(&view).contains("class=\"trailing-whitespace\""));
+
+        view.config.highlight_trailing_whitespace = false;
+        assert!(!has_highlight(&view));
+        assert!(!exported(&view).contains("class=\"trailing-whitespace\""));
+        assert_eq!(view.next_annotation(AnnotationType::TrailingWhitespace), None);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,18 @@
+#[derive(Default, Eq, PartialEq, Clone, Copy, Debug)]
+pub enum SearchCase {
+    /// Case-insensitive unless the query contains an uppercase letter.
+    #[default]
+    Smart,
+    Sensitive,
+    Insensitive,
+}
+
+impl SearchCase {
+    pub fn ignores_case(self, query: &str) -> bool {
+        match self {
+            Self::Smart => !query.chars().any(char::is_uppercase),
+            Self::Sensitive => false,
+            Self::Insensitive => true,
+        }
+    }
+}
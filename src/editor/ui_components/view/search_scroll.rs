@@ -0,0 +1,6 @@
+#[derive(Default, Eq, PartialEq, Clone, Copy, Debug)]
+pub enum SearchScroll {
+    #[default]
+    Center,
+    Minimal,
+}
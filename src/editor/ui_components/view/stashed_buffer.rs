@@ -0,0 +1,15 @@
+use crate::prelude::*;
+
+use std::collections::HashMap;
+
+use super::{Buffer, JumpList};
+
+/// The user's buffer and where they were in it, kept aside while another
+/// buffer (like the help screen) is shown.
+pub struct StashedBuffer {
+    pub buffer: Buffer,
+    pub text_location: Location,
+    pub scroll_offset: Position,
+    pub marks: HashMap<char, Location>,
+    pub jump_list: JumpList,
+}
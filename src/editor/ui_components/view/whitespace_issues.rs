@@ -0,0 +1,21 @@
+#[derive(Default, Clone, Copy, Eq, PartialEq, Debug)]
+pub struct WhitespaceIssues {
+    pub mixed_indentation: bool,
+    pub trailing_whitespace: bool,
+}
+
+impl WhitespaceIssues {
+    pub const fn is_complete(self) -> bool {
+        self.mixed_indentation && self.trailing_whitespace
+    }
+
+    pub fn to_message(self) -> Option<String> {
+        let description = match (self.mixed_indentation, self.trailing_whitespace) {
+            (true, true) => "Mixed indentation and trailing whitespace",
+            (true, false) => "Mixed indentation",
+            (false, true) => "Trailing whitespace",
+            (false, false) => return None,
+        };
+        Some(format!("{description} detected."))
+    }
+}
@@ -7,12 +7,52 @@
     clippy::as_conversions
 )]
 
+use std::{env, process};
+
 mod editor;
-use editor::Editor;
+use editor::{Arguments, Editor, check_file};
 mod prelude;
 use prelude::*;
 
 fn main() {
     let _ = setup_logger();
-    Editor::new().unwrap().run();
+    let arguments = Arguments::parse(env::args());
+    if let Some(option) = &arguments.unknown_option {
+        eprint!("{NAME}: unknown option '{option}'\n\n{}", Arguments::usage());
+        process::exit(2);
+    }
+    if arguments.help {
+        #[allow(clippy::print_stdout)]
+        {
+            print!("{}", Arguments::usage());
+        }
+        return;
+    }
+    if arguments.version {
+        #[allow(clippy::print_stdout)]
+        {
+            println!("{NAME} {VERSION}");
+        }
+        return;
+    }
+    if arguments.check {
+        if arguments.file_names.is_empty() {
+            eprintln!("{NAME}: --check requires a file name");
+            process::exit(2);
+        }
+        let exit_code = arguments
+            .file_names
+            .iter()
+            .map(|file_name| check_file(file_name).exit_code())
+            .max()
+            .unwrap_or_default();
+        process::exit(exit_code);
+    }
+    match Editor::new(&arguments) {
+        Ok(mut editor) => editor.run(),
+        Err(err) => {
+            eprintln!("{NAME}: {err}");
+            process::exit(1);
+        },
+    }
 }
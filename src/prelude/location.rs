@@ -1,7 +1,9 @@
 
 use super::{GraphemeIdx, LineIdx};
 
-#[derive(Copy, Clone, Default)]
+/// A place in the text: a grapheme within a line. A grapheme index equal to
+/// the line's grapheme count is the end of the line.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
 pub struct Location {
     pub grapheme_idx: GraphemeIdx,
     pub line_idx: LineIdx,
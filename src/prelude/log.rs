@@ -1,7 +1,24 @@
+use std::{env, path::PathBuf};
+
 use chrono::Local;
-pub use log::{info, warn};
+use log::LevelFilter;
+
+const LEVEL_VAR: &str = "HECTO_LOG";
+const FILE_VAR: &str = "HECTO_LOG_FILE";
+const DEFAULT_LOG_FILE: &str = "output.log";
 
+/// Logs to a file, never to the terminal the editor draws on. Nothing is
+/// logged unless `HECTO_LOG` names a level (`error` through `trace`);
+/// `HECTO_LOG_FILE` picks the file, `output.log` by default.
 pub fn setup_logger() -> Result<(), fern::InitError> {
+    let level = env::var(LEVEL_VAR)
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::Off);
+    if level == LevelFilter::Off {
+        return Ok(());
+    }
+    let path = env::var_os(FILE_VAR).map_or_else(|| PathBuf::from(DEFAULT_LOG_FILE), PathBuf::from);
     fern::Dispatch::new()
         .format(|out, message, record| {
             let time = Local::now();
@@ -11,10 +28,10 @@ pub fn setup_logger() -> Result<(), fern::InitError> {
                 record.level(),
                 record.target(),
                 message
-            ))
+            ));
         })
-        .level(log::LevelFilter::Debug)
-        .chain(fern::log_file("output.log")?)
+        .level(level)
+        .chain(fern::log_file(path)?)
         .apply()?;
     Ok(())
 }
@@ -1,17 +1,37 @@
+//! Types and constants shared by every module, imported with
+//! `use crate::prelude::*`.
+//!
+//! The index aliases are plain `usize`s and do not stop one kind of index
+//! being passed as another; the names document which space a value lives in.
+//! Text is addressed by [`Location`] (line and grapheme), the screen by
+//! [`Position`] (row and column). Converting between the two depends on the
+//! rendered width of each grapheme, so it is done by the view, not here.
+
+/// Index of a grapheme cluster within a line.
 pub type GraphemeIdx = usize;
+/// Index of a line within a buffer.
 pub type LineIdx = usize;
+/// Byte offset into a line's UTF-8 string.
 pub type ByteIdx = usize;
+/// Screen column; wide graphemes and tabs take up more than one.
 pub type ColIdx = usize;
+/// Screen row.
 pub type RowIdx = usize;
 
 mod location;
 mod log;
 mod position;
+#[cfg(test)]
+mod scratch_dir;
 mod size;
 
 pub use location::Location;
-pub use log::{info, setup_logger, warn};
+pub use log::setup_logger;
 pub use position::Position;
+#[cfg(test)]
+pub use scratch_dir::ScratchDir;
 pub use size::Size;
+/// The program name, used in messages and the welcome screen.
 pub const NAME: &str = env!("CARGO_PKG_NAME");
+/// The program version from `Cargo.toml`.
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
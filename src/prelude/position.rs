@@ -1,5 +1,7 @@
 
 use super::{ColIdx, RowIdx};
+/// A cell on the screen or within the scrolled document, counted in
+/// terminal rows and columns.
 #[derive(Clone, Copy, Default)]
 pub struct Position {
     pub col: ColIdx,
@@ -7,6 +9,20 @@ pub struct Position {
 }
 
 impl Position {
+    /// Takes the row first, the same order as `Location`'s line and
+    /// grapheme in the view.
+    pub const fn new(row: RowIdx, col: ColIdx) -> Self {
+        Self { col, row }
+    }
+
+    pub const fn row(self) -> RowIdx {
+        self.row
+    }
+
+    pub const fn col(self) -> ColIdx {
+        self.col
+    }
+
     pub const fn saturating_sub(self, other: Self) -> Self {
         Self {
             col: self.col.saturating_sub(other.col),
@@ -1,4 +1,5 @@
 
+/// Dimensions in terminal rows (`height`) and columns (`width`).
 #[derive(Default, Clone, Copy, Eq, PartialEq)]
 pub struct Size {
     pub height: usize,